@@ -1,4 +1,4 @@
-use super::parse::CallDef;
+use super::parse::{CallDef, CallOrigin};
 use quote::quote;
 
 /// See the `fn call` docs at the `lib.rs` of this crate for a high level definition.
@@ -23,6 +23,30 @@ pub fn expand_call(def: CallDef) -> proc_macro2::TokenStream {
 		.map(|method| method.args.iter().map(|(_, type_)| type_.clone()).collect::<Vec<_>>())
 		.collect::<Vec<_>>();
 
+	// This is a vector of the declared weight for each of the functions in `fn_name`.
+	let weight = methods.iter().map(|method| &method.weight).collect::<Vec<_>>();
+
+	// This is a vector of the code which checks the origin of each of the functions in `fn_name`,
+	// and turns it into the `caller` passed to the function. Root calls are passed the root account
+	// as their caller, so they can still be called directly, without going through `dispatch`.
+	let ensure_origin = methods
+		.iter()
+		.map(|method| match method.origin {
+			CallOrigin::Signed => quote! {
+				match origin {
+					crate::support::Origin::Signed(who) => who,
+					_ => return Err(crate::support::DispatchError::BadOrigin),
+				}
+			},
+			CallOrigin::Root => quote! {
+				match origin {
+					crate::support::Origin::Root => T::root(),
+					_ => return Err(crate::support::DispatchError::BadOrigin),
+				}
+			},
+		})
+		.collect::<Vec<_>>();
+
 	// This quote block creates an `enum Call` which contains all the calls exposed by our pallet,
 	// and the `Dispatch` trait logic to route a `caller` to access those functions.
 	let dispatch_impl = quote! {
@@ -30,6 +54,8 @@ pub fn expand_call(def: CallDef) -> proc_macro2::TokenStream {
 		//
 		// The parsed function names will be `snake_case`, and that will show up in the enum.
 		#[allow(non_camel_case_types)]
+		#[derive(Debug)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 		pub enum Call<T: Config> {
 			#(
 				#fn_name { #( #args_name: #args_type),* },
@@ -42,19 +68,83 @@ pub fn expand_call(def: CallDef) -> proc_macro2::TokenStream {
 			type Caller = T::AccountId;
 			type Call = Call<T>;
 
-			fn dispatch(&mut self, caller: Self::Caller, call: Self::Call) -> crate::support::DispatchResult {
+			// Every call checks that it accepts the `origin`, and reports its declared weight as the
+			// weight it actually consumed.
+			fn dispatch(
+				&mut self,
+				origin: crate::support::Origin<Self::Caller>,
+				call: Self::Call,
+			) -> crate::support::DispatchResultWithInfo {
 				match call {
 					#(
 						Call::#fn_name { #( #args_name ),* } => {
+							let caller = #ensure_origin;
 							self.#fn_name(
 								// Note that we assume the first argument of every call is the `caller`.
 								caller,
 								#( #args_name ),*
 							)?;
+							Ok(crate::support::PostDispatchInfo {
+								actual_weight: crate::support::Weight(#weight),
+							})
 						},
 					)*
 				}
-				Ok(())
+			}
+		}
+
+		impl<T: Config> Call<T> {
+			// The names of all the callable functions of this pallet, in the order they are declared.
+			pub fn call_names() -> &'static [&'static str] {
+				&[ #( stringify!(#fn_name) ),* ]
+			}
+
+			// The name of the function this call dispatches to.
+			pub fn name(&self) -> &'static str {
+				match self {
+					#(
+						Call::#fn_name { .. } => stringify!(#fn_name),
+					)*
+				}
+			}
+		}
+
+		// The static weight of each call, as declared with `#[weight(..)]` on each function.
+		impl<T: Config> crate::support::GetWeight for Call<T> {
+			fn weight(&self) -> crate::support::Weight {
+				match self {
+					#(
+						Call::#fn_name { .. } => crate::support::Weight(#weight),
+					)*
+				}
+			}
+		}
+
+		// A human-friendly representation of a call, like `transfer { to: "bob", amount: 30 }`.
+		// The arguments are formatted with their `Debug` representation.
+		impl<T: Config> core::fmt::Display for Call<T>
+		where
+			#( #( #args_type: core::fmt::Debug, )* )*
+		{
+			fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				match self {
+					#(
+						Call::#fn_name { #( #args_name ),* } => {
+							let args: &[(&str, &dyn core::fmt::Debug)] = &[
+								#( (stringify!(#args_name), #args_name) ),*
+							];
+							write!(f, "{}", stringify!(#fn_name))?;
+							for (index, (name, value)) in args.iter().enumerate() {
+								let separator = if index == 0 { " { " } else { ", " };
+								write!(f, "{}{}: {:?}", separator, name, value)?;
+							}
+							if !args.is_empty() {
+								write!(f, " }}")?;
+							}
+							Ok(())
+						}
+					)*
+				}
 			}
 		}
 	};
@@ -1,17 +1,22 @@
 pub mod expand;
 pub mod parse;
 
+use quote::ToTokens;
+
 /// See the `fn call` docs at the `lib.rs` of this crate for a high level definition.
 pub fn call(
 	_attr: proc_macro::TokenStream,
 	item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-	// The final expanded code will be placed here.
-	// Since our macro only adds new code, our final product will contain all of our old code too,
-	// hence we clone `item`.
-	let mut finished = item.clone();
 	let item_mod = syn::parse_macro_input!(item as syn::Item);
 
+	// The final expanded code will be placed here.
+	// Since our macro mostly adds new code, our final product will contain all of our old code too.
+	// The only exception are the `#[weight(..)]` and `#[origin(..)]` attributes, which are only
+	// meaningful to this macro, so we remove them from the old code.
+	let mut finished: proc_macro::TokenStream =
+		parse::strip_call_attrs(item_mod.clone()).into_token_stream().into();
+
 	// First we parse the call functions implemented for the pallet...
 	let generated: proc_macro::TokenStream = match parse::CallDef::try_from(item_mod.clone()) {
 		// ..then we generate our new code.
@@ -5,6 +5,8 @@ use syn::spanned::Spanned;
 mod keyword {
 	syn::custom_keyword!(T);
 	syn::custom_keyword!(AccountId);
+	syn::custom_keyword!(signed);
+	syn::custom_keyword!(root);
 }
 
 /// This object will collect all the information we need to keep while parsing the callable
@@ -25,6 +27,34 @@ pub struct CallVariantDef {
 	pub name: syn::Ident,
 	/// Information on args of the function: `(name, type)`.
 	pub args: Vec<(syn::Ident, Box<syn::Type>)>,
+	/// The static weight of the function, declared with `#[weight(..)]`.
+	pub weight: syn::Expr,
+	/// The origin the function accepts, declared with `#[origin(..)]`.
+	pub origin: CallOrigin,
+}
+
+/// The origins a callable function can accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOrigin {
+	/// The function is called by an account which signed the call. This is the default.
+	Signed,
+	/// The function can only be called by the root origin, for example for admin calls.
+	Root,
+}
+
+impl syn::parse::Parse for CallOrigin {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let lookahead = input.lookahead1();
+		if lookahead.peek(keyword::signed) {
+			input.parse::<keyword::signed>()?;
+			Ok(CallOrigin::Signed)
+		} else if lookahead.peek(keyword::root) {
+			input.parse::<keyword::root>()?;
+			Ok(CallOrigin::Root)
+		} else {
+			Err(lookahead.error())
+		}
+	}
 }
 
 impl CallDef {
@@ -74,6 +104,21 @@ impl CallDef {
 
 				let fn_name = method.sig.ident.clone();
 
+				// Every callable function must declare its weight with `#[weight(..)]`.
+				let weight = match method.attrs.iter().find(|attr| attr.path().is_ident("weight")) {
+					Some(attr) => attr.parse_args::<syn::Expr>()?,
+					None => {
+						let msg = "Invalid call, missing `#[weight(..)]` attribute";
+						return Err(syn::Error::new(method.sig.span(), msg))
+					},
+				};
+
+				// Calls accept signed origins, unless they declare otherwise with `#[origin(..)]`.
+				let origin = match method.attrs.iter().find(|attr| attr.path().is_ident("origin")) {
+					Some(attr) => attr.parse_args::<CallOrigin>()?,
+					None => CallOrigin::Signed,
+				};
+
 				// Parsing the rest of the args. Skipping 2 for `self` and `caller`.
 				for arg in method.sig.inputs.iter().skip(2) {
 					// All arguments should be typed.
@@ -96,7 +141,7 @@ impl CallDef {
 				}
 
 				// Store all the function name and the arg data for the function.
-				methods.push(CallVariantDef { name: fn_name, args });
+				methods.push(CallVariantDef { name: fn_name, args, weight, origin });
 			}
 		}
 
@@ -139,3 +184,18 @@ pub fn check_caller_arg(arg: &syn::PatType) -> syn::Result<()> {
 
 	Ok(())
 }
+
+/// Remove the `#[weight(..)]` and `#[origin(..)]` attributes from the callable functions, since
+/// they are not real attributes, and are only used by this macro.
+pub fn strip_call_attrs(mut item: syn::Item) -> syn::Item {
+	if let syn::Item::Impl(item_impl) = &mut item {
+		for impl_item in &mut item_impl.items {
+			if let syn::ImplItem::Fn(method) = impl_item {
+				method
+					.attrs
+					.retain(|attr| !attr.path().is_ident("weight") && !attr.path().is_ident("origin"));
+			}
+		}
+	}
+	item
+}
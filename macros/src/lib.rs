@@ -1,6 +1,25 @@
 mod call;
 mod runtime;
 
+/// Expand the callable functions of a pallet.
+///
+/// This generates:
+/// - `enum Call` - an enum with one variant per callable function, containing its arguments.
+/// - implements the trait `support::Dispatch` to dispatch each `Call` to its function. Successful
+///   calls report their declared weight as the weight they actually consumed.
+/// - every callable function accepts a `support::Origin::Signed` origin, whose account is passed as
+///   the `caller`, unless it declares `#[origin(root)]`. Root calls only accept the
+///   `support::Origin::Root` origin, and are passed `system::Config::root()` as the `caller`. Other
+///   origins are rejected with `DispatchError::BadOrigin`.
+/// - `fn call_names()` on `Call` - which lists the names of all the callable functions, in the order
+///   they are declared.
+/// - `fn name()` on `Call` - which returns the name of the function a call dispatches to.
+/// - implements `Display` for `Call`, like `transfer { to: "bob", amount: 30 }`, using the `Debug`
+///   representation of the arguments.
+/// - with the `serde` feature of the runtime crate, implements `serde::Serialize` and
+///   `serde::Deserialize` for `Call`.
+/// - implements the trait `support::GetWeight` for `Call`. Every callable function must declare its
+///   static weight with a `#[weight(..)]` attribute, for example `#[weight(10)]`.
 #[proc_macro_attribute]
 pub fn call(
 	attr: proc_macro::TokenStream,
@@ -14,17 +33,86 @@ pub fn call(
 /// This generates function implementations on `Runtime`:
 /// - `fn new()` - which generates a new instance of the runtime, by instantiating all the pallets
 ///   included in the runtime.
+/// - `fn new_with_genesis()` - which creates a new instance of the runtime like `fn new()`, and then
+///   seeds it from a `RuntimeGenesisConfig`, which contains the genesis config of every pallet,
+///   including system. Every pallet is expected to define a `GenesisConfig` implementing `Default`,
+///   and a `build_genesis()` function which applies it.
 /// - `fn execute_block()` - which handles basic logic for executing a block of extrinsics. It does
 ///   basic actions like incrementing the block number and checking the block to be executed has a
-///   valid block number.
+///   valid block number. Every pallet, including system, is expected to implement `support::Hooks`,
+///   whose `on_initialize` is called at the start of each block, and whose `on_finalize` is called
+///   at the end of each block which executed successfully. The `Runtime` is expected to implement
+///   `support::ChargeFee`, which is used to charge a fee for each extrinsic before it is dispatched,
+///   including the optional tip of the extrinsic.
+/// - `fn execute_blocks()` - which executes a chain of blocks in order with `execute_block()`, for
+///   example to replay a block log. It stops at the first failing block, and returns its block
+///   number together with the error.
+/// - `fn execute_block_with_report()` - which executes a block like `execute_block()`, but records
+///   failing extrinsics instead of stopping at them, and returns a `support::BlockReport` with the
+///   events, the total weight, the number of successful extrinsics and the failures of the block.
+/// - `fn author_block()` - which starts authoring the next block on top of the current state, and
+///   returns a `PendingBlock`, or an error if the number of the next block would overflow. Extrinsics are checked and applied as they are pushed to the pending
+///   block with `push_extrinsic()`. Rejected extrinsics leave no trace and are recorded, but do not
+///   abort authoring. `seal()` finishes the block, and returns a `support::BlockReport` of it.
+/// - `fn apply_extrinsic()` - which checks and dispatches a single extrinsic like `execute_block()`
+///   does, but outside of a block, so the block number does not advance.
+/// - `fn validate_transaction()` - which checks whether an extrinsic could be included in a block,
+///   for a transaction pool, without applying it.
+/// - `fn snapshot()` and `fn restore()` - which take a `RuntimeSnapshot` of the full state of the
+///   runtime, and restore it later. This requires the `Runtime` to implement `Clone`, so the state of
+///   every pallet must be `Clone` too: the pallets derive `Clone`, which requires every type in their
+///   `Config`, like `AccountId`, `Balance` and `Content`, to be `Clone`.
+/// - `fn execute_block_atomic()` - which executes a block like `execute_block()`, but reverts all of
+///   its state changes if any extrinsic fails, by restoring a snapshot.
+/// - `fn pallet_index()` and `fn pallet_name()` - which map between the name of a pallet and its
+///   index. Pallets are indexed in the order they are declared in the `Runtime` struct, so `system`
+///   always has index 0.
+/// - `fn dry_run()` - which dispatches a single call on a copy of the runtime, and returns the result
+///   without committing any state changes. On success, the result contains the weight the call
+///   actually consumed.
+/// - `fn state_root()` - which computes a deterministic hash over the persistent storage of all the
+///   pallets, using `system::Config::Hashing`. Every pallet, including system, is expected to
+///   implement `support::EncodeStorage`, which leaves out transient state like events.
 ///
 /// This also generates code needed for dispatching calls to the pallets:
 /// - Note: For simplicity, we assume that the system pallet is not callable.
 /// - `enum RuntimeCall` - an "outer"-enum representing the accumulation of all possible calls to
 ///   all pallets. The system pallet is not included.
+/// - `RuntimeCall::batch` - a built-in call which dispatches several calls in order, on behalf of the
+///   same origin. It stops at the first failing call, whose error is returned together with its
+///   position in the batch. Built-in calls are grouped under the pallet name `utility`, which is
+///   indexed after all the other pallets.
+/// - `RuntimeCall::batch_all` - a built-in call like `RuntimeCall::batch`, but which reverts all the
+///   calls of the batch if any of them fails. This requires the `Runtime` to implement `Clone`.
+/// - `RuntimeCall::sudo` - a built-in call which dispatches a call with the `support::Origin::Root`
+///   origin. Only the root account, `system::Config::root()`, can make it, which is how extrinsics
+///   reach root calls.
+/// - `enum RuntimeEvent` - an "outer"-enum representing the accumulation of all possible events of
+///   all pallets, including system. Every pallet is expected to define an `Event` and a
+///   `take_events()` function.
+///   The events are collected into the system pallet after every extrinsic. The `Runtime` is
+///   expected to implement `support::OnEvent`, which is called for every event before it is
+///   collected.
+/// - `fn call_names()` on `RuntimeCall` - which lists the names of the calls of all pallets, as
+///   `pallet::function`.
+/// - `fn pallet_index()` and `fn pallet_name()` on `RuntimeCall` - which return the index and the
+///   name of the pallet of a call. `execute_block()` uses the name to tag the errors of failing
+///   calls with the pallet which returned them.
+/// - implements `Display` for `RuntimeCall`, like `balances::transfer { to: "bob", amount: 30 }`.
+/// - `fn name()` on `RuntimeCall` - which returns the name of a call as `pallet::function`. With the
+///   `logging` feature of the runtime crate, `execute_block()` logs every extrinsic with its name,
+///   caller and outcome at the `debug` level.
+/// - `fn encode()` and `fn decode()` on `RuntimeCall` - which convert a call to bytes and back. These,
+///   and implementations of `serde::Serialize` and `serde::Deserialize` for `RuntimeCall`, are only
+///   generated with the `serde` feature of the runtime crate, which must depend on `serde_json`.
+/// - implements the trait `support::GetWeight` for `RuntimeCall`. Blocks whose total weight exceeds
+///   `system::Config::MAX_BLOCK_WEIGHT` are rejected by `execute_block()`. The weight actually
+///   consumed by every applied extrinsic is added to the block weight of the system pallet.
 /// - implements the trait `support::Dispatch` to dispatch calls to the appropriate pallet. Basic
-///   logic like incrementing the nonce of the user is included in the generated code. The system
-///   pallet is not included.
+///   logic like incrementing the nonce of the user is included in the generated code. Extrinsics are
+///   dispatched with the `support::Origin::Signed` origin of their caller. The system pallet is not
+///   included. The `Runtime` is expected to implement `support::CheckCall`, which can reject every
+///   call, including the calls of a batch, before it is dispatched.
 #[proc_macro_attribute]
 pub fn runtime(
 	attr: proc_macro::TokenStream,
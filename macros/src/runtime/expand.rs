@@ -9,9 +9,94 @@ pub fn expand_runtime(def: RuntimeDef) -> proc_macro2::TokenStream {
 	let pallet_names = pallets.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
 	// This is a vector of all the pallet types, not including system.
 	let pallet_types = pallets.iter().map(|(_, type_)| type_.clone()).collect::<Vec<_>>();
+	// This is a vector of the index of each pallet. Pallets are indexed in the order they are
+	// declared, starting at 1, since index 0 is reserved for system.
+	let pallet_indices = (1..=pallets.len()).map(|index| index as u8).collect::<Vec<_>>();
+	// The utility calls, like `batch`, are built into the runtime since they dispatch to every
+	// other pallet. They are grouped under the name `utility`, which is indexed after all pallets.
+	let utility_index = (pallets.len() + 1) as u8;
 
 	// This quote block implements functions on the `Runtime` struct.
 	let runtime_impl = quote! {
+		// A snapshot of the full state of the runtime, taken with `Runtime::snapshot` and restored
+		// with `Runtime::restore`. The snapshot is independent of the runtime it was taken from, so
+		// it can also be used to fork the state.
+		#[derive(Debug, Clone)]
+		pub struct RuntimeSnapshot(#runtime_struct);
+
+		// A block which is being authored on top of the current state, created with
+		// `Runtime::author_block`. Extrinsics are checked and applied one at a time as they are
+		// pushed, and the block is finished with `seal`.
+		//
+		// The pending block has already started, so it must be sealed before the runtime is used
+		// again.
+		pub struct PendingBlock<'a> {
+			runtime: &'a mut #runtime_struct,
+			block_number: <#runtime_struct as system::Config>::BlockNumber,
+			// The total declared weight of the extrinsics included so far.
+			weight: crate::support::Weight,
+			// The number of extrinsics pushed so far, including the rejected ones.
+			pushed: usize,
+			// The zero-based push position and the error of every rejected extrinsic.
+			rejected: Vec<(usize, crate::support::DispatchError)>,
+		}
+
+		impl<'a> PendingBlock<'a> {
+			// Check and apply `extrinsic`, including it in the block if it succeeds.
+			//
+			// Extrinsics with a bad signature or nonce, whose caller cannot afford the fee, whose
+			// call fails, or which do not fit in the remaining block weight, are rejected without
+			// changing any state. The rejection is returned and recorded, and authoring continues.
+			pub fn push_extrinsic(&mut self, extrinsic: types::Extrinsic) -> crate::support::DispatchResult {
+				let index = self.pushed;
+				self.pushed += 1;
+				let weight = self.weight.saturating_add(crate::support::GetWeight::weight(&extrinsic.call));
+				let result = if weight > <#runtime_struct as system::Config>::MAX_BLOCK_WEIGHT {
+					Err(crate::support::DispatchError::Other("block weight exceeded"))
+				} else {
+					// The extrinsic may fail after changing some state, like its nonce and fee, so we
+					// restore a snapshot to leave no trace of it.
+					let snapshot = self.runtime.snapshot();
+					let result = self.runtime.try_apply_extrinsic(extrinsic).map_err(|(_, error)| error);
+					if result.is_err() {
+						self.runtime.restore(snapshot);
+					}
+					result.map(|_| ())
+				};
+				match result {
+					Ok(()) => self.weight = weight,
+					Err(ref error) => self.rejected.push((index, error.clone())),
+				}
+				result
+			}
+
+			// The zero-based push position and the error of every extrinsic rejected so far.
+			pub fn rejected(&self) -> &[(usize, crate::support::DispatchError)] {
+				&self.rejected
+			}
+
+			// Finish the block, which lets every pallet know the block has ended, and return a report
+			// of the sealed block. Only the extrinsics which were applied successfully are part of
+			// the block.
+			pub fn seal(self) -> crate::support::BlockReport<RuntimeEvent> {
+				self.runtime.finalize_block(self.block_number);
+				crate::support::BlockReport {
+					events: self.runtime.system.events().to_vec(),
+					total_weight: self.runtime.system.block_weight(),
+					success_count: self.runtime.system.extrinsic_count(),
+					failures: self.rejected,
+				}
+			}
+		}
+
+		// The initial state of the runtime, which is applied before block 1 is executed.
+		// Note that it is just an accumulation of the genesis configs of each pallet, including system.
+		#[derive(Debug, Default)]
+		pub struct RuntimeGenesisConfig {
+			pub system: system::GenesisConfig<#runtime_struct>,
+			#( pub #pallet_names: #pallet_names::GenesisConfig<#runtime_struct>, )*
+		}
+
 		impl #runtime_struct {
 			// Create a new instance of the main Runtime, by creating a new instance of each pallet.
 			fn new() -> Self {
@@ -24,60 +109,582 @@ pub fn expand_runtime(def: RuntimeDef) -> proc_macro2::TokenStream {
 				}
 			}
 
-			// Execute a block of extrinsics. Increments the block number.
-			fn execute_block(&mut self, block: types::Block) -> crate::support::DispatchResult {
-				self.system.inc_block_number();
-				if block.header.block_number != self.system.block_number() {
-					return Err(&"block number does not match what is expected")
+			// Create a new instance of the main Runtime, with each pallet seeded from its genesis
+			// config. The pallets build their genesis in the order they are declared, starting with
+			// system.
+			//
+			// Genesis is not part of any block, so the events emitted while building it are dropped,
+			// after the runtime reacted to them like a block would.
+			//
+			// This panics if the genesis config is invalid, for example if it claims the same content
+			// twice.
+			fn new_with_genesis(config: RuntimeGenesisConfig) -> Self {
+				let mut runtime = Self::new();
+				runtime.system.build_genesis(config.system).expect("invalid genesis config");
+				#(
+					runtime
+						.#pallet_names
+						.build_genesis(config.#pallet_names)
+						.expect(concat!("invalid genesis config of ", stringify!(#pallet_names)));
+				)*
+				#(
+					for event in runtime.#pallet_names.take_events() {
+						crate::support::OnEvent::on_event(&mut runtime, &RuntimeEvent::#pallet_names(event));
+					}
+				)*
+				runtime.system.take_events();
+				runtime
+			}
+
+			// Execute a block of extrinsics. Increments the block number, and the nonce of each
+			// caller with a valid nonce. Each caller pays the fee of their call before it is
+			// dispatched. Blocks with an unexpected block number or parent hash, or
+			// which exceed the maximum block weight, are rejected without changing any state.
+			//
+			// Execution stops at the first failing extrinsic, and the returned error contains the
+			// position of that extrinsic in the block and its caller. Note that the state changes of
+			// the extrinsics executed before the failing one are kept.
+			fn execute_block(
+				&mut self,
+				block: types::Block,
+			) -> Result<(), crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>> {
+				self.initialize_block(&block)?;
+				for (index, extrinsic) in block.extrinsics.into_iter().enumerate() {
+					let caller = extrinsic.caller.clone();
+					self.try_apply_extrinsic(extrinsic).map_err(|(pallet, error)| {
+						crate::support::BlockError::ExtrinsicFailed { index, caller, pallet, error }
+					})?;
+				}
+				self.finalize_block(block.header.block_number);
+				Ok(())
+			}
+
+			// Execute a chain of blocks in order with `execute_block`, for example to replay an
+			// exported block log on top of a genesis state.
+			//
+			// Replaying stops at the first block which fails, and the returned error contains the
+			// block number of that block. The blocks executed before it are kept.
+			fn execute_blocks(
+				&mut self,
+				blocks: Vec<types::Block>,
+			) -> Result<
+				(),
+				(
+					<#runtime_struct as system::Config>::BlockNumber,
+					crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>,
+				),
+			> {
+				for block in blocks {
+					let block_number = block.header.block_number;
+					self.execute_block(block).map_err(|error| (block_number, error))?;
+				}
+				Ok(())
+			}
+
+			// Execute a block of extrinsics like `execute_block`, but without stopping at failing
+			// extrinsics, and return a report of the block.
+			//
+			// Failing extrinsics are recorded in the report, and the rest of the block is still
+			// executed. Only an invalid block is returned as an error, in which case no state is
+			// changed.
+			fn execute_block_with_report(
+				&mut self,
+				block: types::Block,
+			) -> Result<
+				crate::support::BlockReport<RuntimeEvent>,
+				crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>,
+			> {
+				self.initialize_block(&block)?;
+				let mut failures = Vec::new();
+				for (index, extrinsic) in block.extrinsics.into_iter().enumerate() {
+					if let Err((_, error)) = self.try_apply_extrinsic(extrinsic) {
+						failures.push((index, error));
+					}
+				}
+				self.finalize_block(block.header.block_number);
+				Ok(crate::support::BlockReport {
+					events: self.system.events().to_vec(),
+					total_weight: self.system.block_weight(),
+					success_count: self.system.extrinsic_count(),
+					failures,
+				})
+			}
+
+			// Check that `block` can be executed on top of the current state, and start it by
+			// initializing all the pallets. No state is changed if the block is invalid.
+			fn initialize_block(
+				&mut self,
+				block: &types::Block,
+			) -> Result<(), crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>> {
+				// Blocks must be executed in order, one after the other.
+				let Some(block_number) = self.system.next_block_number() else {
+					return Err(crate::support::BlockError::InvalidBlock("block number overflow".into()))
+				};
+				if block.header.block_number != block_number {
+					return Err(crate::support::BlockError::InvalidBlock("block number mismatch".into()))
 				}
-				for (i, support::Extrinsic { caller, call }) in block.extrinsics.into_iter().enumerate() {
-					self.system.inc_nonce(&caller);
-					let _res = self.dispatch(caller, call).map_err(|e| {
-						eprintln!(
-							"Extrinsic Error\n\tBlock Number: {}\n\tExtrinsic Number: {}\n\tError: {}",
-							block.header.block_number, i, e
-						)
-					});
+				// The first block has no parent, so it is expected to use the default hash.
+				let parent_hash = self.system.block_hash(self.system.block_number()).unwrap_or_default();
+				if block.header.parent_hash != parent_hash {
+					return Err(crate::support::BlockError::InvalidBlock(
+						"parent hash does not match the previous block".into(),
+					))
 				}
+				// The total weight of the block must not exceed the maximum block weight.
+				let block_weight = block.extrinsics.iter().fold(crate::support::Weight(0), |total, extrinsic| {
+					total.saturating_add(crate::support::GetWeight::weight(&extrinsic.call))
+				});
+				if block_weight > <#runtime_struct as system::Config>::MAX_BLOCK_WEIGHT {
+					return Err(crate::support::BlockError::InvalidBlock("block weight exceeded".into()))
+				}
+				// The block is valid, so we can start executing it. The system pallet is initialized
+				// first, which moves it to the new block number.
+				crate::support::Hooks::on_initialize(&mut self.system, block.header.block_number);
+				// Store the hash of the block, so that it can be looked up later.
+				self.system.set_block_hash(block.header.block_number, block.header.hash::<<#runtime_struct as system::Config>::Hashing>());
+				// Let every pallet know that a new block has started.
+				#(
+					crate::support::Hooks::on_initialize(
+						&mut self.#pallet_names,
+						block.header.block_number,
+					);
+				)*
 				Ok(())
 			}
+
+			// Let every pallet know that the block `block_number` has ended, with the system pallet
+			// last.
+			fn finalize_block(&mut self, block_number: <#runtime_struct as system::Config>::BlockNumber) {
+				#(
+					crate::support::Hooks::on_finalize(&mut self.#pallet_names, block_number);
+				)*
+				crate::support::Hooks::on_finalize(&mut self.system, block_number);
+			}
+
+			// Start authoring the next block on top of the current state, like a block author would.
+			// See `PendingBlock`. This fails if the number of the next block would overflow.
+			fn author_block(
+				&mut self,
+			) -> Result<PendingBlock<'_>, crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>> {
+				let block_number = self.system.next_block_number().ok_or_else(|| {
+					crate::support::BlockError::InvalidBlock("block number overflow".into())
+				})?;
+				let header = crate::support::Header {
+					block_number,
+					parent_hash: self.system.block_hash(self.system.block_number()).unwrap_or_default(),
+				};
+				let block = types::Block { header, extrinsics: Vec::new() };
+				self.initialize_block(&block)?;
+				Ok(PendingBlock {
+					runtime: self,
+					block_number,
+					weight: crate::support::Weight(0),
+					pushed: 0,
+					rejected: Vec::new(),
+				})
+			}
+
+			// Apply a single extrinsic against the current state, without executing a block.
+			//
+			// The extrinsic is checked and dispatched exactly like in `execute_block`, but the block
+			// number does not advance and no hooks are called. This is useful to test extrinsics one
+			// at a time, like a transaction pool would.
+			fn apply_extrinsic(&mut self, extrinsic: types::Extrinsic) -> crate::support::DispatchResult {
+				self.try_apply_extrinsic(extrinsic).map(|_| ()).map_err(|(_, error)| error)
+			}
+
+			// Check whether an extrinsic could be included in a block, without applying it.
+			//
+			// This verifies the signature of the extrinsic, that its nonce is not stale, that its
+			// caller can afford its fee, and that its call passes `support::CheckCall`, so calls which
+			// would certainly be rejected are not pooled. Extrinsics whose nonce is ahead of the next
+			// nonce of their caller are valid, but not ready. The call is not dispatched, so it can
+			// still fail when it is applied.
+			fn validate_transaction(
+				&self,
+				extrinsic: &types::Extrinsic,
+			) -> Result<
+				crate::support::ValidTransaction<<#runtime_struct as crate::support::ChargeFee>::Balance>,
+				crate::support::DispatchError,
+			> {
+				let support::Extrinsic { caller, nonce, call, tip, signature } = extrinsic;
+				if !crate::support::Verify::verify(
+					signature,
+					&crate::support::signing_payload(call, nonce, tip),
+					caller,
+				) {
+					return Err(crate::support::DispatchError::InvalidSignature);
+				}
+				let next_nonce = self.system.nonce(caller);
+				if *nonce < next_nonce {
+					return Err(crate::support::DispatchError::InvalidNonce);
+				}
+				crate::support::ChargeFee::can_pay_fee(self, caller, call, tip)?;
+				crate::support::CheckCall::check_call(self, &crate::support::Origin::Signed(caller.clone()), call)?;
+				Ok(crate::support::ValidTransaction {
+					priority: <#runtime_struct as crate::support::ChargeFee>::fee(call, tip),
+					ready: *nonce == next_nonce,
+				})
+			}
+
+			// Apply a single extrinsic: verify its signature and nonce, charge its fee, and dispatch
+			// its call. On success, the extrinsic and the weight it actually consumed are counted, and
+			// the events emitted by the pallets are collected. On failure, the error is tagged with
+			// the pallet which returned it, or `None` if it was rejected before reaching any pallet.
+			fn try_apply_extrinsic(
+				&mut self,
+				extrinsic: types::Extrinsic,
+			) -> Result<crate::support::PostDispatchInfo, (Option<&'static str>, crate::support::DispatchError)> {
+				let support::Extrinsic { caller, nonce, call, tip, signature } = extrinsic;
+				#[cfg(feature = "logging")]
+				let call_name = call.name();
+				let pallet = call.pallet_name();
+				let result = if !crate::support::Verify::verify(
+					&signature,
+					&crate::support::signing_payload(&call, &nonce, &tip),
+					&caller,
+				) {
+					// Extrinsics which were not signed by their caller are rejected.
+					Err((None, crate::support::DispatchError::InvalidSignature))
+				} else {
+					// Extrinsics with an unexpected nonce are rejected, which prevents replays.
+					self.system.check_nonce(&caller, nonce).and_then(|()| {
+						// The fee is checked before the nonce is incremented, so that callers which
+						// cannot pay do not change any state, like the nonce or the account count.
+						crate::support::ChargeFee::can_pay_fee(self, &caller, &call, &tip)?;
+						self.system.inc_nonce(&caller)?;
+						// The fee, including the tip, is paid before the call is dispatched, and is
+						// not refunded if the call fails.
+						crate::support::ChargeFee::charge_fee(self, &caller, &call, &tip)
+					})
+					// The errors above happen before the call reaches any pallet.
+					.map_err(|error| (None, error))
+					.and_then(|()| {
+						// The errors of the call are tagged with the pallet which returned them.
+						self.dispatch(crate::support::Origin::Signed(caller.clone()), call)
+							.map_err(|error| (Some(pallet), error))
+					})
+				};
+				// When the `logging` feature is enabled, every extrinsic and its outcome is logged.
+				#[cfg(feature = "logging")]
+				match &result {
+					Ok(_) => log::debug!("dispatch {} by {} -> Ok", call_name, caller),
+					Err((_, error)) => log::debug!("dispatch {} by {} -> Err: {}", call_name, caller, error),
+				}
+				// Collect the events emitted by the pallets during this extrinsic. This also happens
+				// when the extrinsic failed, since a failing call, like a batch, may have changed some
+				// state before its error, and the runtime must react to those changes too.
+				#(
+					for event in self.#pallet_names.take_events() {
+						let event = RuntimeEvent::#pallet_names(event);
+						crate::support::OnEvent::on_event(self, &event);
+						self.system.deposit_event(event);
+					}
+				)*
+				// The system pallet emits its events while the runtime reacts to the pallet events,
+				// so they are collected last.
+				for event in self.system.take_events() {
+					self.system.deposit_event(RuntimeEvent::system(event));
+				}
+				let info = result?;
+				self.system.inc_extrinsic_count();
+				self.system.register_weight(info.actual_weight);
+				Ok(info)
+			}
+
+			// Take a snapshot of the full state of the runtime. This requires the runtime to
+			// implement `Clone`.
+			fn snapshot(&self) -> RuntimeSnapshot {
+				RuntimeSnapshot(self.clone())
+			}
+
+			// Restore the state of the runtime to a `snapshot`, discarding all the changes made
+			// since it was taken.
+			fn restore(&mut self, snapshot: RuntimeSnapshot) {
+				*self = snapshot.0;
+			}
+
+			// Execute a block of extrinsics with all-or-nothing semantics.
+			//
+			// This behaves like `execute_block`, but if any extrinsic fails, all the state changes
+			// made by the block are reverted before returning the error, by restoring a snapshot
+			// taken before the block.
+			fn execute_block_atomic(
+				&mut self,
+				block: types::Block,
+			) -> Result<(), crate::support::BlockError<<#runtime_struct as system::Config>::AccountId>> {
+				let snapshot = self.snapshot();
+				let result = self.execute_block(block);
+				if result.is_err() {
+					self.restore(snapshot);
+				}
+				result
+			}
+
+			// Get the index of the pallet `name`. Pallets are indexed in the order they are declared
+			// in the runtime, so `system` always has index 0.
+			fn pallet_index(name: &str) -> Option<u8> {
+				match name {
+					"system" => Some(0),
+					#( stringify!(#pallet_names) => Some(#pallet_indices), )*
+					"utility" => Some(#utility_index),
+					_ => None,
+				}
+			}
+
+			// Get the name of the pallet with index `index`. This is the inverse of `pallet_index`.
+			fn pallet_name(index: u8) -> Option<&'static str> {
+				match index {
+					0 => Some("system"),
+					#( #pallet_indices => Some(stringify!(#pallet_names)), )*
+					#utility_index => Some("utility"),
+					_ => None,
+				}
+			}
+
+			// Dispatch a call on behalf of a caller without committing any state changes.
+			//
+			// This is useful to check whether a call would succeed before submitting it. The call
+			// is dispatched on a copy of the runtime, which is thrown away afterwards. Note that
+			// nonces and fees are not checked. On success, the weight the call actually consumed is
+			// returned, which is useful for benchmarking.
+			fn dry_run(
+				&self,
+				caller: <#runtime_struct as system::Config>::AccountId,
+				call: RuntimeCall,
+			) -> crate::support::DispatchResultWithInfo {
+				crate::support::Dispatch::dispatch(
+					&mut self.clone(),
+					crate::support::Origin::Signed(caller),
+					call,
+				)
+			}
+
+			// Compute a hash over the entire state of the runtime, which can be used to check that
+			// two runtimes are in the same state.
+			//
+			// The persistent storage of each pallet is encoded in the order the pallets are declared,
+			// starting with system. Transient state, like the events of the current block, is left
+			// out, so the root only depends on the state which is kept from one block to the next.
+			fn state_root(&self) -> <#runtime_struct as system::Config>::Hash {
+				let mut encoded = crate::support::EncodeStorage::encode_storage(&self.system);
+				#(
+					encoded.push('/');
+					encoded.push_str(&crate::support::EncodeStorage::encode_storage(&self.#pallet_names));
+				)*
+				<<#runtime_struct as system::Config>::Hashing as crate::support::Hashing>::hash(
+					encoded.as_bytes(),
+				)
+			}
 		}
 	};
 
-	// This quote block implements the `RuntimeCall` enum and implements the `Dispatch` trait.
+	// This quote block implements the `RuntimeCall` and `RuntimeEvent` enums, and implements the
+	// `Dispatch` trait.
 	let dispatch_impl = quote! {
 		// These are all the calls which are exposed to the world.
 		// Note that it is just an accumulation of the calls exposed by each pallet.
 		//
 		// The parsed function names will be `snake_case`, and that will show up in the enum.
 		#[allow(non_camel_case_types)]
+		#[derive(Debug)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 		pub enum RuntimeCall {
-			#( #pallet_names(#pallet_names::Call<#runtime_struct>) ),*
+			#( #pallet_names(#pallet_names::Call<#runtime_struct>), )*
+			// Dispatch several calls in order on behalf of the same origin, stopping at the first
+			// call which fails. The calls before the failing one are not reverted.
+			batch(Vec<RuntimeCall>),
+			// Dispatch several calls in order on behalf of the same origin, reverting all of them
+			// if any call fails.
+			batch_all(Vec<RuntimeCall>),
+			// Dispatch a call with the root origin. Only the root account can make this call, which
+			// is how root calls are reached from a block.
+			sudo(Box<RuntimeCall>),
+		}
+
+		// These are all the events which can be emitted by the runtime.
+		// Note that it is just an accumulation of the events emitted by each pallet, including system.
+		#[allow(non_camel_case_types)]
+		#[derive(Debug, Clone)]
+		pub enum RuntimeEvent {
+			system(system::Event<#runtime_struct>),
+			#( #pallet_names(#pallet_names::Event<#runtime_struct>) ),*
+		}
+
+		impl RuntimeCall {
+			// The name of the pallet this call is dispatched to.
+			pub fn pallet_name(&self) -> &'static str {
+				match self {
+					#( RuntimeCall::#pallet_names(_) => stringify!(#pallet_names), )*
+					RuntimeCall::batch(_) | RuntimeCall::batch_all(_) | RuntimeCall::sudo(_) => "utility",
+				}
+			}
+
+			// The index of the pallet this call is dispatched to, see `Runtime::pallet_index`.
+			pub fn pallet_index(&self) -> u8 {
+				match self {
+					#( RuntimeCall::#pallet_names(_) => #pallet_indices, )*
+					RuntimeCall::batch(_) | RuntimeCall::batch_all(_) | RuntimeCall::sudo(_) => {
+						#utility_index
+					}
+				}
+			}
+
+			// The names of all the calls exposed by the runtime, in the form `pallet::function`.
+			pub fn call_names() -> Vec<String> {
+				let mut names = Vec::new();
+				#(
+					for name in #pallet_names::Call::<#runtime_struct>::call_names() {
+						names.push(format!("{}::{}", stringify!(#pallet_names), name));
+					}
+				)*
+				names.push("utility::batch".to_string());
+				names.push("utility::batch_all".to_string());
+				names.push("utility::sudo".to_string());
+				names
+			}
+
+			// The name of this call, in the form `pallet::function`.
+			pub fn name(&self) -> String {
+				match self {
+					#(
+						RuntimeCall::#pallet_names(call) => {
+							format!("{}::{}", stringify!(#pallet_names), call.name())
+						}
+					)*
+					RuntimeCall::batch(_) => "utility::batch".to_string(),
+					RuntimeCall::batch_all(_) => "utility::batch_all".to_string(),
+					RuntimeCall::sudo(_) => "utility::sudo".to_string(),
+				}
+			}
+		}
+
+		// With the `serde` feature, calls can be encoded to bytes and decoded back, for example to
+		// send them over a wire or store them. The encoding is JSON.
+		#[cfg(feature = "serde")]
+		impl RuntimeCall {
+			// Encode this call into bytes.
+			pub fn encode(&self) -> Vec<u8> {
+				serde_json::to_vec(self).expect("calls are always serializable")
+			}
+
+			// Decode a call from bytes produced by `encode`.
+			pub fn decode(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+				serde_json::from_slice(bytes)
+			}
+		}
+
+		// A human-friendly representation of a call, like `balances::transfer { to: "bob", amount: 30 }`.
+		// The calls of a batch, and the call of a sudo, are listed in brackets.
+		impl core::fmt::Display for RuntimeCall {
+			fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				match self {
+					#(
+						RuntimeCall::#pallet_names(call) => {
+							write!(f, "{}::{}", stringify!(#pallet_names), call)
+						}
+					)*
+					RuntimeCall::batch(calls) | RuntimeCall::batch_all(calls) => {
+						write!(f, "{} [", self.name())?;
+						for (index, call) in calls.iter().enumerate() {
+							let separator = if index == 0 { "" } else { ", " };
+							write!(f, "{}{}", separator, call)?;
+						}
+						write!(f, "]")
+					}
+					RuntimeCall::sudo(call) => write!(f, "{} [{}]", self.name(), call),
+				}
+			}
+		}
+
+		// The weight of a `RuntimeCall` is the weight of the underlying pallet call.
+		// The weight of a batch is the total weight of its calls, and the weight of a sudo is the
+		// weight of its call.
+		impl crate::support::GetWeight for RuntimeCall {
+			fn weight(&self) -> crate::support::Weight {
+				match self {
+					#(
+						RuntimeCall::#pallet_names(call) => crate::support::GetWeight::weight(call),
+					)*
+					RuntimeCall::batch(calls) | RuntimeCall::batch_all(calls) => {
+						calls.iter().fold(crate::support::Weight(0), |total, call| {
+							total.saturating_add(crate::support::GetWeight::weight(call))
+						})
+					}
+					RuntimeCall::sudo(call) => crate::support::GetWeight::weight(call.as_ref()),
+				}
+			}
 		}
 
 		impl crate::support::Dispatch for #runtime_struct {
 			type Caller = <Runtime as system::Config>::AccountId;
 			type Call = RuntimeCall;
-			// Dispatch a call on behalf of a caller. Increments the caller's nonce.
+			// Dispatch a call on behalf of an origin.
 			//
 			// Dispatch allows us to identify which underlying pallet call we want to execute.
-			// Note that we extract the `caller` from the extrinsic, and use that information
-			// to determine who we are executing the call on behalf of.
+			// Note that extrinsics are dispatched with the `Signed` origin of their `caller`, which
+			// determines who we are executing the call on behalf of. The pallet checks that the
+			// call accepts the origin.
 			fn dispatch(
 				&mut self,
-				caller: Self::Caller,
+				origin: crate::support::Origin<Self::Caller>,
 				runtime_call: Self::Call,
-			) -> crate::support::DispatchResult {
+			) -> crate::support::DispatchResultWithInfo {
+				// The runtime may reject the call before it reaches any pallet.
+				crate::support::CheckCall::check_call(self, &origin, &runtime_call)?;
 				// This match statement will allow us to correctly route `RuntimeCall`s
 				// to the appropriate pallet level call.
 				match runtime_call {
 					#(
-						RuntimeCall::#pallet_names(call) => {
-							self.#pallet_names.dispatch(caller, call)?;
+						RuntimeCall::#pallet_names(call) => self.#pallet_names.dispatch(origin, call),
+					)*
+					// The calls of a batch are dispatched recursively, and the error of the first
+					// failing call is returned together with its position in the batch. A batch
+					// consumes the total weight of its calls.
+					RuntimeCall::batch(calls) => {
+						let mut actual_weight = crate::support::Weight(0);
+						for (index, call) in calls.into_iter().enumerate() {
+							let info = self.dispatch(origin.clone(), call).map_err(|error| {
+								crate::support::DispatchError::BatchInterrupted {
+									index,
+									error: Box::new(error),
+								}
+							})?;
+							actual_weight = actual_weight.saturating_add(info.actual_weight);
 						}
-					),*
+						Ok(crate::support::PostDispatchInfo { actual_weight })
+					}
+					// The state is snapshot before an atomic batch, and restored if any of its
+					// calls fails, so either all the calls take effect or none of them do.
+					RuntimeCall::batch_all(calls) => {
+						let snapshot = self.snapshot();
+						let mut actual_weight = crate::support::Weight(0);
+						for (index, call) in calls.into_iter().enumerate() {
+							match self.dispatch(origin.clone(), call) {
+								Ok(info) => actual_weight = actual_weight.saturating_add(info.actual_weight),
+								Err(error) => {
+									self.restore(snapshot);
+									return Err(crate::support::DispatchError::BatchInterrupted {
+										index,
+										error: Box::new(error),
+									});
+								}
+							}
+						}
+						Ok(crate::support::PostDispatchInfo { actual_weight })
+					}
+					// Extrinsics are always signed, so the root account reaches root calls by
+					// wrapping them in a sudo, which dispatches them with the root origin.
+					RuntimeCall::sudo(call) => {
+						match origin {
+							crate::support::Origin::Signed(who)
+								if who == <#runtime_struct as system::Config>::root() => {}
+							crate::support::Origin::Root => {}
+							_ => return Err(crate::support::DispatchError::BadOrigin),
+						}
+						self.dispatch(crate::support::Origin::Root, *call)
+					}
 				}
-				Ok(())
 			}
 		}
 	};
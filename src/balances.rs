@@ -1,20 +1,184 @@
-use std::{collections::BTreeMap, fmt::Debug};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::String,
+    vec::Vec,
+};
+use core::fmt::Debug;
 
-use num::{CheckedAdd, CheckedSub, Zero};
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Zero};
 
-use crate::support::DispatchResult;
+use crate::support::{DispatchError, DispatchResult, EncodeStorage, Hooks};
 
 pub trait Config: crate::system::Config {
-    type Balance: Zero + CheckedSub + CheckedAdd + Copy + Debug;
+    type Balance: Zero
+        + CheckedSub
+        + CheckedAdd
+        + CheckedMul
+        + CheckedDiv
+        + Copy
+        + Debug
+        + PartialOrd;
+    /// The minimum balance an account must hold to be kept in storage.
+    /// Accounts whose balance drops below this amount, or to zero, are pruned.
+    const EXISTENTIAL_DEPOSIT: Self::Balance;
+    /// The minimum amount of a transfer, which discourages spamming the chain with tiny transfers.
+    const MIN_TRANSFER: Self::Balance;
+    /// The identifier of an asset. Besides the native token, whose balances are tracked by the
+    /// rest of this module, accounts can hold and transfer any number of other assets.
+    type AssetId: Ord + Clone + Debug;
+    /// The asset identifier reserved for the native token.
+    const NATIVE_ASSET: Self::AssetId;
+    /// The account which receives transaction fees, like a treasury, or `None` to burn them.
+    fn fee_recipient() -> Option<Self::AccountId>;
+    /// The number of most recent blocks whose final balances are kept, so they can be queried
+    /// with `balance_at`, or `None` to keep no history.
+    const HISTORY_DEPTH: Option<Self::BlockNumber>;
+}
+
+/// The events which can be emitted by the balances module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<T: Config> {
+    /// `amount` was transferred from `from` to `to`.
+    Transferred {
+        from: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    },
+    /// `amount` of the asset `asset_id` was transferred from `from` to `to`.
+    AssetTransferred {
+        asset_id: T::AssetId,
+        from: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    },
+    /// The account `account` was created, because it received funds.
+    Endowed { account: T::AccountId },
+    /// The account `account` was pruned, because its balance dropped below the existential
+    /// deposit.
+    Reaped { account: T::AccountId },
+}
+
+/// The initial state of the balances module, which is applied before block 1 is executed.
+#[derive(Debug, Clone)]
+pub struct GenesisConfig<T: Config> {
+    /// The initial balances of accounts, which are minted into existence.
+    pub balances: Vec<(T::AccountId, T::Balance)>,
+}
+
+impl<T: Config> Default for GenesisConfig<T> {
+    fn default() -> Self {
+        Self {
+            balances: Vec::new(),
+        }
+    }
+}
+
+/// The identifier of a lock, which describes its purpose, for example `*b"staking "`.
+pub type LockId = [u8; 8];
+
+/// The balance of an account, split into a free and a reserved part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountData<Balance> {
+    /// The part of the balance which can be spent, for example by a transfer.
+    pub free: Balance,
+    /// The part of the balance which is set aside, for example as a deposit. It cannot be spent
+    /// until it is unreserved.
+    pub reserved: Balance,
+}
+
+/// An amount of tokens which was added to or removed from accounts, without adjusting the total
+/// issuance yet.
+///
+/// An imbalance must be resolved exactly once, either by settling it into the total issuance with
+/// `Pallet::settle`, or by pairing it off against an opposite imbalance with `offset`. It cannot
+/// be cloned, so it cannot be settled twice.
+#[must_use = "an imbalance must be settled, or the total issuance is wrong"]
+#[derive(Debug, PartialEq)]
+pub enum Imbalance<Balance> {
+    /// Tokens were added to accounts, so the total issuance must increase.
+    Positive(Balance),
+    /// Tokens were removed from accounts, so the total issuance must decrease.
+    Negative(Balance),
+}
+
+impl<Balance: CheckedAdd + CheckedSub + Copy + PartialOrd> Imbalance<Balance> {
+    /// Get the amount of this imbalance, without resolving it.
+    pub fn peek(&self) -> Balance {
+        match self {
+            Imbalance::Positive(amount) | Imbalance::Negative(amount) => *amount,
+        }
+    }
+
+    /// Pair this imbalance off against `other`, returning the single imbalance which remains.
+    /// For example, tokens removed from one account and added to another cancel out, like a
+    /// transfer.
+    /// This function returns an error if adding two imbalances of the same kind overflows.
+    pub fn offset(self, other: Self) -> Result<Self, DispatchError> {
+        use Imbalance::{Negative, Positive};
+        match (self, other) {
+            (Positive(a), Positive(b)) => {
+                Ok(Positive(a.checked_add(&b).ok_or(DispatchError::Overflow)?))
+            }
+            (Negative(a), Negative(b)) => {
+                Ok(Negative(a.checked_add(&b).ok_or(DispatchError::Overflow)?))
+            }
+            (Positive(a), Negative(b)) | (Negative(b), Positive(a)) => match a.checked_sub(&b) {
+                Some(remaining) => Ok(Positive(remaining)),
+                None => Ok(Negative(b.checked_sub(&a).ok_or(DispatchError::Underflow)?)),
+            },
+        }
+    }
 }
 
 /// This is the Balances Module.
 /// It is a simple module which keeps track of how much balance each account has in this state
 /// machine.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T::AccountId: serde::Serialize, T::Balance: serde::Serialize, T::AssetId: serde::Serialize, T::BlockNumber: serde::Serialize",
+        deserialize = "T::AccountId: serde::Deserialize<'de>, T::Balance: serde::Deserialize<'de>, T::AssetId: serde::Deserialize<'de>, T::BlockNumber: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Pallet<T: Config> {
-    // A simple storage mapping from accounts (`String`) to their balances (`u128`).
-    balances: BTreeMap<T::AccountId, T::Balance>,
+    // A simple storage mapping from accounts (`String`) to their free and reserved balances.
+    balances: BTreeMap<T::AccountId, AccountData<T::Balance>>,
+    // The total amount of tokens which exist in this state machine.
+    total_issuance: T::Balance,
+    // A storage mapping from `(owner, spender)` to the amount `spender` may transfer on behalf of
+    // `owner`.
+    // JSON only supports string keys, so this map is serialized as a list of pairs.
+    #[cfg_attr(feature = "serde", serde(with = "crate::support::map_as_pairs"))]
+    allowances: BTreeMap<(T::AccountId, T::AccountId), T::Balance>,
+    // A storage mapping from accounts to the locks on their free balance.
+    locks: BTreeMap<T::AccountId, Vec<(LockId, T::Balance)>>,
+    // A storage mapping from `(asset_id, account)` to the balance of an asset other than the native
+    // token. Accounts without any balance of an asset are not stored.
+    #[cfg_attr(feature = "serde", serde(with = "crate::support::map_as_pairs"))]
+    assets: BTreeMap<(T::AssetId, T::AccountId), T::Balance>,
+    // The accounts which are frozen, and can neither send nor receive funds.
+    frozen: BTreeSet<T::AccountId>,
+    // A storage mapping from `(block_number, account)` to the free balance of the account at the
+    // end of that block, for the last `Config::HISTORY_DEPTH` blocks. Accounts which did not exist
+    // at the end of a block are not stored.
+    #[cfg_attr(feature = "serde", serde(with = "crate::support::map_as_pairs"))]
+    history: BTreeMap<(T::BlockNumber, T::AccountId), T::Balance>,
+    // The oldest and the newest block in the history, if any. This is stored separately, since
+    // blocks at the end of which no account existed have no entries in `history`.
+    history_range: Option<(T::BlockNumber, T::BlockNumber)>,
+    // The events emitted by this module which have not been taken yet.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<Event<T>>,
+}
+
+impl<T: Config> Default for Pallet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Config> Pallet<T> {
@@ -22,19 +186,620 @@ impl<T: Config> Pallet<T> {
     pub fn new() -> Self {
         Self {
             balances: BTreeMap::new(),
+            total_issuance: T::Balance::zero(),
+            allowances: BTreeMap::new(),
+            locks: BTreeMap::new(),
+            assets: BTreeMap::new(),
+            frozen: BTreeSet::new(),
+            history: BTreeMap::new(),
+            history_range: None,
+            events: Vec::new(),
         }
     }
-    /// Set the balance of an account `who` to some `amount`.
-    pub fn set_balance(&mut self, who: &T::AccountId, amount: T::Balance) {
+    /// Apply the genesis `config` to this module, by minting the initial balances.
+    pub fn build_genesis(&mut self, config: GenesisConfig<T>) -> DispatchResult {
+        for (who, amount) in config.balances {
+            self.mint(who.clone(), who, amount)?;
+        }
+        Ok(())
+    }
+
+    /// Set the free balance of an account `who` to some `amount`.
+    /// Note that this does not touch the total issuance, see `set_balance_checked` for that.
+    pub fn set_balance_unchecked(&mut self, who: &T::AccountId, amount: T::Balance) {
         /* Insert `amount` into the BTreeMap under `who`. */
-        self.balances.insert(who.clone(), amount);
+        let mut data = self.account(who);
+        data.free = amount;
+        self.balances.insert(who.clone(), data);
     }
 
-    /// Get the balance of an account `who`.
+    /// Set the free balance of an account `who` to some `amount`, applying the difference with the
+    /// old balance to the total issuance, so that it stays equal to the sum of all balances.
+    /// If `amount` is below the existential deposit, the account is pruned, or `WouldKillAccount`
+    /// is returned if it has locks.
+    pub fn set_balance_checked(
+        &mut self,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        // - Check that the account is not pruned while it has locks.
+        self.ensure_keeps_locks(who, amount)?;
+
+        // - Use safe math to apply the difference with the old balance to the total issuance.
+        let old_balance = self.balance(who);
+        self.total_issuance = if amount >= old_balance {
+            let increase = amount
+                .checked_sub(&old_balance)
+                .ok_or(DispatchError::Underflow)?;
+            self.total_issuance
+                .checked_add(&increase)
+                .ok_or(DispatchError::Overflow)?
+        } else {
+            let decrease = old_balance
+                .checked_sub(&amount)
+                .ok_or(DispatchError::Underflow)?;
+            self.total_issuance
+                .checked_sub(&decrease)
+                .ok_or(DispatchError::Underflow)?
+        };
+
+        // - Insert the new balance of `who`, pruning the account if needed.
+        self.reduce_balance_to(who, amount)
+    }
+
+    /// Get the balance of the asset `asset_id` held by the account `who`.
+    /// For the native asset, this is the free balance of `who`.
+    pub fn asset_balance(&self, asset_id: &T::AssetId, who: &T::AccountId) -> T::Balance {
+        if asset_id == &T::NATIVE_ASSET {
+            return self.balance(who);
+        }
+        self.assets
+            .get(&(asset_id.clone(), who.clone()))
+            .copied()
+            .unwrap_or_else(T::Balance::zero)
+    }
+
+    /// Set the balance of the asset `asset_id`, other than the native token, held by `who`.
+    /// Zero balances are removed from storage.
+    fn set_asset_balance(&mut self, asset_id: &T::AssetId, who: &T::AccountId, amount: T::Balance) {
+        let key = (asset_id.clone(), who.clone());
+        if amount.is_zero() {
+            self.assets.remove(&key);
+        } else {
+            self.assets.insert(key, amount);
+        }
+    }
+
+    /// Get the free balance of an account `who`, which is the balance it can spend.
     /// If the account has no stored balance, we return zero.
     pub fn balance(&self, who: &T::AccountId) -> T::Balance {
         /* Return the balance of `who`, returning zero if `None`. */
-        *self.balances.get(who).unwrap_or(&T::Balance::zero())
+        self.account(who).free
+    }
+
+    /// Get the reserved balance of an account `who`.
+    /// If the account has no stored balance, we return zero.
+    pub fn reserved_balance(&self, who: &T::AccountId) -> T::Balance {
+        self.account(who).reserved
+    }
+
+    /// Get both the free and the reserved balance of an account `who`.
+    pub fn account(&self, who: &T::AccountId) -> AccountData<T::Balance> {
+        self.balances.get(who).copied().unwrap_or(AccountData {
+            free: T::Balance::zero(),
+            reserved: T::Balance::zero(),
+        })
+    }
+
+    /// Move `amount` from the free balance of `who` to its reserved balance.
+    /// This function returns an error if `who` does not have enough unlocked free balance.
+    pub fn reserve(&mut self, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let mut data = self.account(who);
+        data.free = data
+            .free
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientFunds)?;
+        self.ensure_unlocked(who, data.free)?;
+        data.reserved = data
+            .reserved
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+        self.balances.insert(who.clone(), data);
+        Ok(())
+    }
+
+    /// Move `amount` from the reserved balance of `who` back to its free balance.
+    /// This function returns an error if `who` does not have enough reserved balance.
+    pub fn unreserve(&mut self, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let mut data = self.account(who);
+        data.reserved = data
+            .reserved
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientReserved)?;
+        data.free = data
+            .free
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+        self.balances.insert(who.clone(), data);
+        Ok(())
+    }
+
+    /// Get the free balance of an account `who` at the end of the block `block_number`.
+    /// Returns `None` if that block is not part of the retained history, see
+    /// `Config::HISTORY_DEPTH`. Accounts which did not exist at the end of the block had a balance
+    /// of zero.
+    pub fn balance_at(
+        &self,
+        who: &T::AccountId,
+        block_number: T::BlockNumber,
+    ) -> Option<T::Balance> {
+        let (oldest, newest) = self.history_range?;
+        if block_number < oldest || block_number > newest {
+            return None;
+        }
+        Some(
+            self.history
+                .get(&(block_number, who.clone()))
+                .copied()
+                .unwrap_or_else(T::Balance::zero),
+        )
+    }
+
+    /// Get the total amount of tokens which exist in this state machine.
+    pub fn total_issuance(&self) -> T::Balance {
+        self.total_issuance
+    }
+
+    /// Get the amount `spender` is allowed to transfer on behalf of `owner`.
+    /// If no allowance is stored, we return zero.
+    pub fn allowance(&self, owner: &T::AccountId, spender: &T::AccountId) -> T::Balance {
+        *self
+            .allowances
+            .get(&(owner.clone(), spender.clone()))
+            .unwrap_or(&T::Balance::zero())
+    }
+
+    /// Get the events emitted by this module which have not been taken yet.
+    pub fn events(&self) -> &[Event<T>] {
+        &self.events
+    }
+
+    /// Take all the events emitted by this module, leaving no events behind.
+    pub fn take_events(&mut self) -> Vec<Event<T>> {
+        core::mem::take(&mut self.events)
+    }
+
+    /// Lock `amount` of the free balance of `who` for the purpose `id`, replacing any previous lock
+    /// with the same `id`. Locked funds stay in the account, but cannot be transferred or burned.
+    ///
+    /// Locks overlap rather than stack: the amount which cannot be spent is the largest lock, not
+    /// the sum of all locks. For example, locks of 30 and 60 only prevent spending 60.
+    pub fn set_lock(&mut self, id: LockId, who: &T::AccountId, amount: T::Balance) {
+        let mut locks = self.locks.remove(who).unwrap_or_default();
+        match locks.iter_mut().find(|(lock_id, _)| *lock_id == id) {
+            Some(lock) => lock.1 = amount,
+            None => locks.push((id, amount)),
+        }
+        self.locks.insert(who.clone(), locks);
+    }
+
+    /// Remove the lock `id` from the account `who`, if it exists.
+    pub fn remove_lock(&mut self, id: LockId, who: &T::AccountId) {
+        if let Some(locks) = self.locks.get_mut(who) {
+            locks.retain(|(lock_id, _)| *lock_id != id);
+            if locks.is_empty() {
+                self.locks.remove(who);
+            }
+        }
+    }
+
+    /// Get the amount of the free balance of `who` which is locked, which is the largest lock.
+    pub fn locked_balance(&self, who: &T::AccountId) -> T::Balance {
+        let mut locked = T::Balance::zero();
+        for (_, amount) in self.locks.get(who).into_iter().flatten() {
+            if *amount > locked {
+                locked = *amount;
+            }
+        }
+        locked
+    }
+
+    /// Check that the free balance of `who` can be reduced to `new_balance` without spending any
+    /// locked funds.
+    fn ensure_unlocked(&self, who: &T::AccountId, new_balance: T::Balance) -> DispatchResult {
+        if new_balance < self.locked_balance(who) {
+            return Err(DispatchError::BalanceLocked);
+        }
+        Ok(())
+    }
+
+    /// Check whether the account `who` is frozen.
+    pub fn is_frozen(&self, who: &T::AccountId) -> bool {
+        self.frozen.contains(who)
+    }
+
+    /// Check whether `from` could transfer `amount` to `to`, without changing any state.
+    pub fn can_transfer(&self, from: &T::AccountId, to: &T::AccountId, amount: T::Balance) -> bool {
+        self.check_transfer(from, to, amount).is_ok()
+    }
+
+    /// Transfer `amount` from `from` to `to`, which is the logic behind the `transfer` call.
+    /// Only checked math is used, so this never panics: any invalid input returns an error, and
+    /// leaves the state unchanged.
+    pub fn try_transfer(
+        &mut self,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        // - Check that the transfer is valid, and calculate the new balances.
+        let (new_from_balance, new_to_balance) = self.check_transfer(from, to, amount)?;
+
+        // - Insert the new balance of `from`, pruning the account if needed.
+        self.reduce_balance_to(from, new_from_balance)?;
+
+        // - Insert the new balance of `to`.
+        self.set_free_balance(to, new_to_balance);
+
+        // - Emit an event for the successful transfer.
+        self.events.push(Event::Transferred {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Check that `from` can transfer `amount` to `to`, and calculate the new balances of `from`
+    /// and `to` after the transfer. This is the validation logic shared by `transfer`,
+    /// `can_transfer` and every payout of `transfer_many`.
+    fn check_transfer(
+        &self,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: T::Balance,
+    ) -> Result<(T::Balance, T::Balance), DispatchError> {
+        // - Reject transfers which would be a no-op.
+        if amount.is_zero() {
+            return Err(DispatchError::ZeroAmount);
+        }
+        if amount < T::MIN_TRANSFER {
+            return Err(DispatchError::BelowMinimumTransfer);
+        }
+        if from == to {
+            return Err(DispatchError::SelfTransfer);
+        }
+
+        // - Frozen accounts can neither send nor receive funds.
+        if self.is_frozen(from) || self.is_frozen(to) {
+            return Err(DispatchError::AccountFrozen);
+        }
+
+        // - Use safe math to calculate a `new_from_balance`.
+        let new_from_balance = self
+            .balance(from)
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientFunds)?;
+
+        // - Check that the transfer does not spend any locked funds.
+        self.ensure_unlocked(from, new_from_balance)?;
+
+        // - Use safe math to calculate a `new_to_balance`.
+        let new_to_balance = Self::credit(self.balance(to), amount)?;
+
+        Ok((new_from_balance, new_to_balance))
+    }
+
+    /// Calculate the new balance of a recipient whose balance is `balance`, after receiving
+    /// `amount`. This function returns an error if the new balance overflows.
+    fn credit(balance: T::Balance, amount: T::Balance) -> Result<T::Balance, DispatchError> {
+        balance.checked_add(&amount).ok_or(DispatchError::Overflow)
+    }
+
+    /// Iterate over all accounts and their free balances, ordered by account.
+    pub fn accounts(&self) -> impl Iterator<Item = (&T::AccountId, &T::Balance)> {
+        self.balances.iter().map(|(who, data)| (who, &data.free))
+    }
+
+    /// Remove the account `who`, like dust removal does.
+    /// Its remaining free and reserved balance is removed from the total issuance. Everything else
+    /// stored for `who` is removed as well: its locks, its balances of the other assets, the
+    /// allowances it gave or received, and whether it is frozen.
+    pub fn kill_account(&mut self, who: &T::AccountId) -> DispatchResult {
+        let data = self.account(who);
+        self.total_issuance = self
+            .total_issuance
+            .checked_sub(&data.free)
+            .and_then(|issuance| issuance.checked_sub(&data.reserved))
+            .ok_or(DispatchError::Underflow)?;
+        self.balances.remove(who);
+        self.locks.remove(who);
+        self.assets.retain(|(_, account), _| account != who);
+        self.allowances
+            .retain(|(owner, spender), _| owner != who && spender != who);
+        self.frozen.remove(who);
+        Ok(())
+    }
+
+    /// Withdraw a transaction fee of `amount` from the account `who`.
+    /// Fees are credited to `Config::fee_recipient` if there is one. Otherwise they are burned,
+    /// which reduces the total issuance accordingly.
+    /// Fees are subject to the same checks as transfers, see `check_withdraw_fee`.
+    /// If the balance of `who` drops below the existential deposit, the account is pruned.
+    pub fn withdraw_fee(&mut self, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let new_balance = self.check_withdraw_fee(who, amount)?;
+        match T::fee_recipient() {
+            // The fee recipient pays its fees to itself.
+            Some(recipient) if &recipient == who => Ok(()),
+            Some(_) if amount.is_zero() => Ok(()),
+            Some(recipient) => {
+                let new_recipient_balance = self
+                    .balance(&recipient)
+                    .checked_add(&amount)
+                    .ok_or(DispatchError::Overflow)?;
+                self.reduce_balance_to(who, new_balance)?;
+                self.set_free_balance(&recipient, new_recipient_balance);
+                Ok(())
+            }
+            None => {
+                self.reduce_balance_to(who, new_balance)?;
+                self.settle(Imbalance::Negative(amount))
+            }
+        }
+    }
+
+    /// Check whether `who` can pay a transaction fee of `amount`, without changing any state, and
+    /// calculate the new balance of `who` after paying it.
+    /// Like a transfer, a fee cannot be paid by a frozen account, or with locked funds. This
+    /// function returns an error if `who` cannot pay the fee.
+    pub fn check_withdraw_fee(
+        &self,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> Result<T::Balance, DispatchError> {
+        let new_balance = self
+            .balance(who)
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientFunds)?;
+        if !amount.is_zero() {
+            if self.is_frozen(who) {
+                return Err(DispatchError::AccountFrozen);
+            }
+            self.ensure_unlocked(who, new_balance)?;
+        }
+        Ok(new_balance)
+    }
+
+    /// Accrue interest on the free balance of `who`, at a rate of `rate_numerator /
+    /// rate_denominator`, for example `1 / 100` for 1% per block. The interest is minted into the
+    /// account, increasing the total issuance, and is returned. The interest is rounded down.
+    /// This function returns an error if the rate denominator is zero, or if any step overflows, in
+    /// which case nothing changes.
+    pub fn accrue_interest(
+        &mut self,
+        who: &T::AccountId,
+        rate_numerator: T::Balance,
+        rate_denominator: T::Balance,
+    ) -> Result<T::Balance, DispatchError> {
+        if rate_denominator.is_zero() {
+            return Err(DispatchError::DivisionByZero);
+        }
+
+        // - Use safe math to calculate the `interest`, and the new balances.
+        let balance = self.balance(who);
+        let interest = balance
+            .checked_mul(&rate_numerator)
+            .ok_or(DispatchError::Overflow)?
+            .checked_div(&rate_denominator)
+            .ok_or(DispatchError::DivisionByZero)?;
+        if interest.is_zero() {
+            return Ok(interest);
+        }
+        let new_balance = balance
+            .checked_add(&interest)
+            .ok_or(DispatchError::Overflow)?;
+        let new_total_issuance = self
+            .total_issuance
+            .checked_add(&interest)
+            .ok_or(DispatchError::Overflow)?;
+
+        // - Insert the new balance of `who`, and update the total issuance.
+        self.set_free_balance(who, new_balance);
+        self.total_issuance = new_total_issuance;
+
+        Ok(interest)
+    }
+
+    /// Resolve an `imbalance` by adjusting the total issuance: a positive imbalance increases it,
+    /// and a negative imbalance reduces it.
+    /// This function returns an error if the total issuance overflows or underflows.
+    pub fn settle(&mut self, imbalance: Imbalance<T::Balance>) -> DispatchResult {
+        self.total_issuance = match imbalance {
+            Imbalance::Positive(amount) => self
+                .total_issuance
+                .checked_add(&amount)
+                .ok_or(DispatchError::Overflow)?,
+            Imbalance::Negative(amount) => self
+                .total_issuance
+                .checked_sub(&amount)
+                .ok_or(DispatchError::Underflow)?,
+        };
+        Ok(())
+    }
+
+    /// Add `amount` to the free balance of `who`, creating the account if needed.
+    /// The total issuance is not increased: the returned positive imbalance must be settled.
+    /// This function returns an error if the balance of `who` overflows.
+    pub fn deposit(
+        &mut self,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> Result<Imbalance<T::Balance>, DispatchError> {
+        let new_balance = self
+            .balance(who)
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+        self.set_free_balance(who, new_balance);
+        Ok(Imbalance::Positive(amount))
+    }
+
+    /// Remove `amount` from the free balance of `who`, like `burn` does.
+    /// The total issuance is not reduced: the returned negative imbalance must be settled.
+    /// This function returns an error if `who` has less than `amount` of unlocked balance.
+    /// If the balance of `who` drops below the existential deposit, the account is pruned.
+    pub fn withdraw(
+        &mut self,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> Result<Imbalance<T::Balance>, DispatchError> {
+        let new_balance = self
+            .balance(who)
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientFunds)?;
+        self.ensure_unlocked(who, new_balance)?;
+        self.reduce_balance_to(who, new_balance)?;
+        Ok(Imbalance::Negative(amount))
+    }
+
+    /// Slash up to `amount` from the free balance of `who` as a penalty. Unlike `burn`, this is
+    /// not initiated by `who`, so locked funds are slashed as well. The slashed amount is capped at
+    /// the free balance of `who`.
+    /// The total issuance is not reduced: the returned negative imbalance must be settled, or
+    /// paired off, for example to reward whoever reported the offence.
+    /// If the balance of `who` drops below the existential deposit, the account is pruned. Accounts
+    /// with locks cannot be pruned, so they keep the existential deposit instead.
+    pub fn slash(&mut self, who: &T::AccountId, amount: T::Balance) -> Imbalance<T::Balance> {
+        let free = self.balance(who);
+        let slashable = if self.would_prune_locks(who) {
+            free.checked_sub(&T::EXISTENTIAL_DEPOSIT)
+                .unwrap_or_else(T::Balance::zero)
+        } else {
+            free
+        };
+        let slashed = if amount > slashable {
+            slashable
+        } else {
+            amount
+        };
+        // The new balance cannot underflow, since `slashed` is at most the free balance of `who`.
+        // For the same reason, and since locked accounts keep the existential deposit, removing
+        // the dust of `who` cannot fail either.
+        let new_balance = free.checked_sub(&slashed).unwrap_or_else(T::Balance::zero);
+        let _ = self.reduce_balance_to(who, new_balance);
+        Imbalance::Negative(slashed)
+    }
+
+    /// Reduce the free balance of an account `who` to some `amount`.
+    /// If `amount` is zero or below the existential deposit, the remaining dust is removed from the
+    /// total issuance, and the account is pruned from storage unless it has a reserved balance.
+    /// Pruning an account would also remove its locks, so this returns `WouldKillAccount` instead
+    /// if the account has any locks.
+    fn reduce_balance_to(&mut self, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        self.ensure_keeps_locks(who, amount)?;
+        if amount.is_zero() || amount < T::EXISTENTIAL_DEPOSIT {
+            self.total_issuance = self
+                .total_issuance
+                .checked_sub(&amount)
+                .ok_or(DispatchError::Underflow)?;
+            if self.reserved_balance(who).is_zero() {
+                if self.balances.remove(who).is_some() {
+                    self.events.push(Event::Reaped {
+                        account: who.clone(),
+                    });
+                }
+                self.locks.remove(who);
+            } else {
+                self.set_balance_unchecked(who, T::Balance::zero());
+            }
+        } else {
+            self.set_free_balance(who, amount);
+        }
+        Ok(())
+    }
+
+    /// Check whether pruning the account `who` would remove some locks. Accounts with a reserved
+    /// balance are never pruned.
+    fn would_prune_locks(&self, who: &T::AccountId) -> bool {
+        self.locks.contains_key(who) && self.reserved_balance(who).is_zero()
+    }
+
+    /// Check that reducing the free balance of `who` to `amount` would not prune the account while
+    /// it has locks, returning `WouldKillAccount` otherwise.
+    fn ensure_keeps_locks(&self, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        if (amount.is_zero() || amount < T::EXISTENTIAL_DEPOSIT) && self.would_prune_locks(who) {
+            return Err(DispatchError::WouldKillAccount);
+        }
+        Ok(())
+    }
+
+    /// Set the free balance of an account `who` to some `amount`, emitting `Endowed` if this
+    /// creates the account.
+    fn set_free_balance(&mut self, who: &T::AccountId, amount: T::Balance) {
+        if !self.balances.contains_key(who) {
+            self.events.push(Event::Endowed {
+                account: who.clone(),
+            });
+        }
+        self.set_balance_unchecked(who, amount);
+    }
+}
+
+impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+    /// At the end of every block, the final balance of every account is recorded in the history,
+    /// and the blocks older than `Config::HISTORY_DEPTH` are forgotten.
+    fn on_finalize(&mut self, block_number: T::BlockNumber) {
+        let Some(depth) = T::HISTORY_DEPTH else {
+            return;
+        };
+        for (who, account) in &self.balances {
+            self.history
+                .insert((block_number, who.clone()), account.free);
+        }
+        let mut oldest = match self.history_range {
+            Some((oldest, _)) => oldest,
+            None => block_number,
+        };
+        if let Some(forgotten) = block_number.checked_sub(&depth) {
+            self.history.retain(|(block, _), _| *block > forgotten);
+            if oldest <= forgotten {
+                // This only overflows with a depth of zero, when no block is kept at all.
+                match forgotten.checked_add(&T::BlockNumber::one()) {
+                    Some(next) => oldest = next,
+                    None => {
+                        self.history_range = None;
+                        return;
+                    }
+                }
+            }
+        }
+        self.history_range = if oldest <= block_number {
+            Some((oldest, block_number))
+        } else {
+            None
+        };
+    }
+}
+
+impl<T: Config> EncodeStorage for Pallet<T>
+where
+    T::AccountId: Debug,
+{
+    /// Everything but the events which have not been taken yet.
+    fn encode_storage(&self) -> String {
+        format!(
+            "{:?}",
+            (
+                &self.balances,
+                &self.total_issuance,
+                &self.allowances,
+                &self.locks,
+                &self.assets,
+                &self.frozen,
+                &self.history,
+                &self.history_range,
+            )
+        )
     }
 }
 
@@ -43,84 +808,558 @@ impl<T: Config> Pallet<T> {
     /// Transfer `amount` from one account to another.
     /// This function verifies that `from` has at least `amount` balance to transfer,
     /// and that no mathematical overflows occur.
+    /// If the balance of `caller` drops below the existential deposit, the account is pruned.
+    /// Transfers of zero, and transfers where `caller` and `to` are the same, are rejected.
+    #[weight(10)]
     pub fn transfer(
         &mut self,
         caller: T::AccountId,
         to: T::AccountId,
         amount: T::Balance,
     ) -> DispatchResult {
-        // - Get the balance of account `caller`.
-        let caller_balance = self.balance(&caller);
+        self.try_transfer(&caller, &to, amount)
+    }
 
-        // - Get the balance of account `to`.
-        let to_balance = self.balance(&to);
+    /// Transfer `amount` from one account to another, like `transfer`, but without killing the
+    /// account of `caller`.
+    /// This function returns an error if the balance of `caller` would drop below the existential
+    /// deposit, instead of pruning the account.
+    #[weight(10)]
+    pub fn transfer_keep_alive(
+        &mut self,
+        caller: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        let (new_caller_balance, _) = self.check_transfer(&caller, &to, amount)?;
+        if new_caller_balance < T::EXISTENTIAL_DEPOSIT {
+            return Err(DispatchError::WouldKillAccount);
+        }
+        self.transfer(caller, to, amount)
+    }
 
-        // - Use safe math to calculate a `new_caller_balance`.
-        let new_caller_balance = caller_balance
-            .checked_sub(&amount)
-            .ok_or("Not enough funds")?;
+    /// Transfer to multiple recipients at once, for example for payouts.
+    /// This function is atomic: every payout is checked like a `transfer`, and `caller` must be able
+    /// to afford the total of all `payouts`, before anything is transferred. If the total
+    /// overflows, or any payout is invalid, nothing is transferred.
+    #[weight(30)]
+    pub fn transfer_many(
+        &mut self,
+        caller: T::AccountId,
+        payouts: Vec<(T::AccountId, T::Balance)>,
+    ) -> DispatchResult {
+        // - Check every payout like a `transfer`, and use safe math to calculate the total and the
+        //   new balances of the recipients, which may receive several payouts.
+        let mut total = T::Balance::zero();
+        let mut new_balances = BTreeMap::new();
+        for (to, amount) in &payouts {
+            self.check_transfer(&caller, to, *amount)?;
+            total = total.checked_add(amount).ok_or(DispatchError::Overflow)?;
+            let balance = match new_balances.get(to) {
+                Some(balance) => *balance,
+                None => self.balance(to),
+            };
+            new_balances.insert(to.clone(), Self::credit(balance, *amount)?);
+        }
 
-        // - Use safe math to calculate a `new_to_balance`.
-        let new_to_balance = to_balance.checked_add(&amount).ok_or("Overflow")?;
+        // - Check that `caller` can afford the total without spending any locked funds.
+        let new_caller_balance = self
+            .balance(&caller)
+            .checked_sub(&total)
+            .ok_or(DispatchError::InsufficientFunds)?;
+        self.ensure_unlocked(&caller, new_caller_balance)?;
 
-        // - Insert the new balance of `caller`.
-        self.set_balance(&caller, new_caller_balance);
+        // - Move the total out of the account of `caller` at once, pruning it if needed, and into
+        //   the accounts of the recipients.
+        self.reduce_balance_to(&caller, new_caller_balance)?;
+        for (to, new_balance) in new_balances {
+            self.set_free_balance(&to, new_balance);
+        }
 
-        // - Insert the new balance of `to`.
-        self.set_balance(&to, new_to_balance);
+        // - Emit an event for every payout.
+        for (to, amount) in payouts {
+            self.events.push(Event::Transferred {
+                from: caller.clone(),
+                to,
+                amount,
+            });
+        }
 
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::Config;
-    use super::Pallet;
+    /// Transfer `amount` from the account `from` to the account `to`, regardless of who signed the
+    /// transfer. This function returns an error unless `caller` is the root account.
+    #[origin(root)]
+    #[weight(10)]
+    pub fn force_transfer(
+        &mut self,
+        caller: T::AccountId,
+        from: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        self.transfer(from, to, amount)
+    }
 
-    struct TestConfig;
-    impl Config for TestConfig {
-        type Balance = u128;
+    /// Set the balance of the account `who` to `amount`, adjusting the total issuance by the
+    /// difference. This function returns an error unless `caller` is the root account.
+    /// If `amount` is below the existential deposit, the account is pruned.
+    #[origin(root)]
+    #[weight(10)]
+    pub fn set_balance(
+        &mut self,
+        caller: T::AccountId,
+        who: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        self.set_balance_checked(&who, amount)
     }
-    impl crate::system::Config for TestConfig {
-        type AccountId = String;
-        type BlockNumber = u32;
-        type Nonce = u32;
+
+    /// Transfer the entire unlocked balance of `caller` to the account `to`.
+    /// Accounts with locks cannot be pruned, so they keep at least the existential deposit.
+    /// This function returns an error if `caller` has no unlocked balance to transfer.
+    #[weight(10)]
+    pub fn transfer_all(&mut self, caller: T::AccountId, to: T::AccountId) -> DispatchResult {
+        // - Get the full unlocked balance of account `caller`.
+        let mut kept = self.locked_balance(&caller);
+        if self.would_prune_locks(&caller) && kept < T::EXISTENTIAL_DEPOSIT {
+            kept = T::EXISTENTIAL_DEPOSIT;
+        }
+        let amount = self
+            .balance(&caller)
+            .checked_sub(&kept)
+            .unwrap_or_else(T::Balance::zero);
+        if amount.is_zero() {
+            return Err(DispatchError::InsufficientFunds);
+        }
+
+        // - Transfer everything, which leaves `caller` with only the balance it has to keep.
+        self.transfer(caller, to, amount)
     }
 
-    #[test]
-    fn init_balances() {
-        /* TODO: Create a mutable variable `balances`, which is a new instance of `Pallet`. */
-        let mut balances = Pallet::<TestConfig>::new();
+    /// Approve `spender` to transfer up to `amount` on behalf of `caller`.
+    /// This overwrites any previous allowance of `spender`.
+    #[weight(5)]
+    pub fn approve(
+        &mut self,
+        caller: T::AccountId,
+        spender: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            self.allowances.remove(&(caller, spender));
+        } else {
+            self.allowances.insert((caller, spender), amount);
+        }
+        Ok(())
+    }
 
-        /* TODO: Assert that the balance of `alice` starts at zero. */
-        assert_eq!(balances.balance(&"alice".to_string()), 0);
+    /// Transfer `amount` from `owner` to `to` on behalf of `owner`.
+    /// This function verifies that `caller` has been approved by `owner` to spend at least
+    /// `amount`, and decreases the allowance accordingly.
+    #[weight(15)]
+    pub fn transfer_from(
+        &mut self,
+        caller: T::AccountId,
+        owner: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        // - Use safe math to calculate the `new_allowance` of `caller`.
+        let new_allowance = self
+            .allowance(&owner, &caller)
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientAllowance)?;
 
-        /* TODO: Set the balance of `alice` to 100. */
-        balances.set_balance(&"alice".to_string(), 100);
+        // - Move the balance from `owner` to `to`.
+        self.transfer(owner.clone(), to, amount)?;
 
-        /* TODO: Assert the balance of `alice` is now 100. */
-        assert_eq!(balances.balance(&"alice".to_string()), 100);
+        // - Insert the new allowance of `caller`.
+        self.approve(owner, caller, new_allowance)
+    }
 
-        /* TODO: Assert the balance of `bob` has not changed and is 0. */
-        assert_eq!(balances.balance(&"bob".to_string()), 0);
+    /// Mint `amount` of new tokens into the account `to`.
+    /// This function verifies that no mathematical overflows occur.
+    ///
+    /// NOTE: There is no permission check here, so any account can mint tokens to any other
+    /// account. This is fine for our simple tutorial, but a real blockchain would restrict who is
+    /// allowed to create new tokens.
+    #[weight(10)]
+    pub fn mint(
+        &mut self,
+        _caller: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        // - Use safe math to calculate a `new_to_balance`.
+        let new_to_balance = self
+            .balance(&to)
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+
+        // - Use safe math to calculate the `new_total_issuance`.
+        let new_total_issuance = self
+            .total_issuance
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+
+        // - Insert the new balance of `to`.
+        self.set_free_balance(&to, new_to_balance);
+
+        // - Update the total issuance.
+        self.total_issuance = new_total_issuance;
+
+        Ok(())
     }
 
-    #[test]
-    fn transfer_balance() {
-        /* TODO: Create a test that checks the following:
-            - That `alice` cannot transfer funds she does not have.
-            - That `alice` can successfully transfer funds to `bob`.
-            - That the balance of `alice` and `bob` is correctly updated.
-        */
-        let mut balances = Pallet::<TestConfig>::new();
-        assert_eq!(
-            balances
-                .transfer("alice".to_string(), "bob".to_string(), 100)
-                .is_err(),
+    /// Burn `amount` of tokens from the balance of `caller`.
+    /// This function verifies that `caller` has at least `amount` balance to burn, and reduces the
+    /// total issuance accordingly.
+    /// If the balance of `caller` drops below the existential deposit, the account is pruned.
+    #[weight(10)]
+    pub fn burn(&mut self, caller: T::AccountId, amount: T::Balance) -> DispatchResult {
+        // - Remove the tokens from `caller`, pruning the account if needed.
+        let imbalance = self.withdraw(&caller, amount)?;
+
+        // - Reduce the total issuance accordingly.
+        self.settle(imbalance)
+    }
+
+    /// Transfer `amount` of the asset `asset_id` from one account to another.
+    /// Transfers of the native asset behave exactly like `transfer`. Other assets have no
+    /// existential deposit, minimum transfer or locks, but zero transfers, transfers to self and
+    /// transfers from or to frozen accounts are still rejected.
+    #[weight(10)]
+    pub fn transfer_asset(
+        &mut self,
+        caller: T::AccountId,
+        asset_id: T::AssetId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        if asset_id == T::NATIVE_ASSET {
+            return self.try_transfer(&caller, &to, amount);
+        }
+
+        // - Reject transfers which would be a no-op.
+        if amount.is_zero() {
+            return Err(DispatchError::ZeroAmount);
+        }
+        if caller == to {
+            return Err(DispatchError::SelfTransfer);
+        }
+
+        // - Frozen accounts can neither send nor receive any asset.
+        if self.is_frozen(&caller) || self.is_frozen(&to) {
+            return Err(DispatchError::AccountFrozen);
+        }
+
+        // - Use safe math to calculate the new balances of `caller` and `to`.
+        let new_caller_balance = self
+            .asset_balance(&asset_id, &caller)
+            .checked_sub(&amount)
+            .ok_or(DispatchError::InsufficientFunds)?;
+        let new_to_balance = self
+            .asset_balance(&asset_id, &to)
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+
+        // - Insert the new balances.
+        self.set_asset_balance(&asset_id, &caller, new_caller_balance);
+        self.set_asset_balance(&asset_id, &to, new_to_balance);
+
+        self.events.push(Event::AssetTransferred {
+            asset_id,
+            from: caller,
+            to,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Mint `amount` of the asset `asset_id` into the account `to`.
+    /// Minting the native asset behaves exactly like `mint`. The total issuance only tracks the
+    /// native asset.
+    ///
+    /// NOTE: Like `mint`, there is no permission check here, so any account can mint any asset.
+    /// Both mint calls follow the same trust model of our simple tutorial.
+    #[weight(10)]
+    pub fn mint_asset(
+        &mut self,
+        caller: T::AccountId,
+        asset_id: T::AssetId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        if asset_id == T::NATIVE_ASSET {
+            return self.mint(caller, to, amount);
+        }
+
+        // - Use safe math to calculate a `new_to_balance`.
+        let new_to_balance = self
+            .asset_balance(&asset_id, &to)
+            .checked_add(&amount)
+            .ok_or(DispatchError::Overflow)?;
+        self.set_asset_balance(&asset_id, &to, new_to_balance);
+
+        Ok(())
+    }
+
+    /// Freeze the account `who`, so that it can neither send nor receive transfers.
+    /// This function returns an error unless `caller` is the root account.
+    #[origin(root)]
+    #[weight(10)]
+    pub fn freeze(&mut self, caller: T::AccountId, who: T::AccountId) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        self.frozen.insert(who);
+        Ok(())
+    }
+
+    /// Thaw the frozen account `who`, so that it can send and receive transfers again.
+    /// This function returns an error unless `caller` is the root account.
+    #[origin(root)]
+    #[weight(10)]
+    pub fn thaw(&mut self, caller: T::AccountId, who: T::AccountId) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        self.frozen.remove(&who);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccountData;
+    use super::Config;
+    use super::Event;
+    use super::Imbalance;
+    use super::Pallet;
+    use crate::support::{DispatchError, Hooks};
+    use num::{Bounded, One, Zero};
+
+    // All the test configs use the same system config, which only differs in the type it is
+    // implemented for.
+    macro_rules! impl_system_config {
+        ($($config:ty),*) => {
+            $(
+                impl crate::system::Config for $config {
+                    type AccountId = String;
+                    type BlockNumber = u32;
+                    type Nonce = u32;
+                    type RuntimeEvent = ();
+                    type Hash = u64;
+                    type Hashing = crate::support::DefaultHashing;
+                    type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+                    const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+                    fn root() -> String {
+                        "root".to_string()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_system_config!(
+        TestConfig,
+        MinTransferConfig,
+        TreasuryConfig,
+        U64Config,
+        U16Config
+    );
+
+    #[derive(Debug, PartialEq)]
+    struct TestConfig;
+    impl Config for TestConfig {
+        type Balance = u128;
+        const EXISTENTIAL_DEPOSIT: u128 = 10;
+        const MIN_TRANSFER: u128 = 1;
+        type AssetId = u32;
+        const NATIVE_ASSET: u32 = 0;
+        fn fee_recipient() -> Option<String> {
+            None
+        }
+        const HISTORY_DEPTH: Option<u32> = Some(2);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MinTransferConfig;
+    impl Config for MinTransferConfig {
+        type Balance = u128;
+        const EXISTENTIAL_DEPOSIT: u128 = 1;
+        const MIN_TRANSFER: u128 = 5;
+        type AssetId = u32;
+        const NATIVE_ASSET: u32 = 0;
+        fn fee_recipient() -> Option<String> {
+            None
+        }
+        const HISTORY_DEPTH: Option<u32> = None;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct TreasuryConfig;
+    impl Config for TreasuryConfig {
+        type Balance = u128;
+        const EXISTENTIAL_DEPOSIT: u128 = 10;
+        const MIN_TRANSFER: u128 = 1;
+        type AssetId = u32;
+        const NATIVE_ASSET: u32 = 0;
+        fn fee_recipient() -> Option<String> {
+            Some("treasury".to_string())
+        }
+        const HISTORY_DEPTH: Option<u32> = None;
+    }
+
+    // The same pallet with smaller balance types, to catch assumptions about their range.
+    #[derive(Debug, PartialEq)]
+    struct U64Config;
+    impl Config for U64Config {
+        type Balance = u64;
+        const EXISTENTIAL_DEPOSIT: u64 = 1;
+        const MIN_TRANSFER: u64 = 1;
+        type AssetId = u32;
+        const NATIVE_ASSET: u32 = 0;
+        fn fee_recipient() -> Option<String> {
+            None
+        }
+        const HISTORY_DEPTH: Option<u32> = None;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct U16Config;
+    impl Config for U16Config {
+        type Balance = u16;
+        const EXISTENTIAL_DEPOSIT: u16 = 1;
+        const MIN_TRANSFER: u16 = 1;
+        type AssetId = u32;
+        const NATIVE_ASSET: u32 = 0;
+        fn fee_recipient() -> Option<String> {
+            None
+        }
+        const HISTORY_DEPTH: Option<u32> = None;
+    }
+
+    /// Check that the issuance math of a pallet holds at the maximum value of its balance type.
+    fn balances_at_the_max_value<T>()
+    where
+        T: Config<AccountId = String>,
+        T::Balance: Bounded + One,
+    {
+        let max = T::Balance::max_value();
+        let one = T::Balance::one();
+        let zero = T::Balance::zero();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut balances = Pallet::<T>::new();
+        assert_eq!(balances.set_balance_checked(&alice, max), Ok(()));
+        assert_eq!(balances.total_issuance(), max);
+
+        // The total issuance is at its maximum, so no more tokens can be created.
+        assert_eq!(
+            balances.set_balance_checked(&bob, one),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(
+            balances.mint(alice.clone(), bob.clone(), one),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(
+            balances.accrue_interest(&alice, one, one),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(
+            Imbalance::Positive(max).offset(Imbalance::Positive(one)),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.balance(&bob), zero);
+        assert_eq!(balances.total_issuance(), max);
+
+        // The whole supply can be transferred at once.
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), max), Ok(()));
+        assert_eq!(balances.balance(&alice), zero);
+        assert_eq!(balances.balance(&bob), max);
+        assert_eq!(balances.total_issuance(), max);
+
+        // Burned tokens can be minted again.
+        assert_eq!(balances.burn(bob.clone(), one), Ok(()));
+        assert_eq!(balances.mint(bob.clone(), alice.clone(), one), Ok(()));
+        assert_eq!(balances.balance(&alice), one);
+        assert_eq!(balances.total_issuance(), max);
+    }
+
+    #[test]
+    fn u128_balances_at_the_max_value() {
+        balances_at_the_max_value::<TestConfig>();
+    }
+
+    #[test]
+    fn u64_balances_at_the_max_value() {
+        balances_at_the_max_value::<U64Config>();
+    }
+
+    #[test]
+    fn u16_balances_at_the_max_value() {
+        balances_at_the_max_value::<U16Config>();
+    }
+
+    #[test]
+    fn u16_interest_overflow() {
+        let mut balances = Pallet::<U16Config>::new();
+        let alice = "alice".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 1000), Ok(()));
+
+        // 1000 * 100 does not fit in a `u16`, even though the interest itself would.
+        assert_eq!(
+            balances.accrue_interest(&alice, 100, 1000),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.accrue_interest(&alice, 50, 1000), Ok(50));
+        assert_eq!(balances.total_issuance(), 1050);
+    }
+
+    #[test]
+    fn default_balances() {
+        let balances = Pallet::<TestConfig>::default();
+        assert_eq!(balances.total_issuance(), 0);
+        assert_eq!(balances.accounts().count(), 0);
+    }
+
+    #[test]
+    fn init_balances() {
+        /* TODO: Create a mutable variable `balances`, which is a new instance of `Pallet`. */
+        let mut balances = Pallet::<TestConfig>::new();
+
+        /* TODO: Assert that the balance of `alice` starts at zero. */
+        assert_eq!(balances.balance(&"alice".to_string()), 0);
+
+        /* TODO: Set the balance of `alice` to 100. */
+        balances.set_balance_unchecked(&"alice".to_string(), 100);
+
+        /* TODO: Assert the balance of `alice` is now 100. */
+        assert_eq!(balances.balance(&"alice".to_string()), 100);
+
+        /* TODO: Assert the balance of `bob` has not changed and is 0. */
+        assert_eq!(balances.balance(&"bob".to_string()), 0);
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn transfer_balance() {
+        /* TODO: Create a test that checks the following:
+            - That `alice` cannot transfer funds she does not have.
+            - That `alice` can successfully transfer funds to `bob`.
+            - That the balance of `alice` and `bob` is correctly updated.
+        */
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(
+            balances
+                .transfer("alice".to_string(), "bob".to_string(), 100)
+                .is_err(),
             true
         );
-        balances.set_balance(&"alice".to_string(), 100);
+        balances.set_balance_unchecked(&"alice".to_string(), 100);
         assert_eq!(
             balances
                 .transfer("alice".to_string(), "bob".to_string(), 100)
@@ -130,4 +1369,992 @@ mod tests {
         assert_eq!(balances.balance(&"alice".to_string()), 0);
         assert_eq!(balances.balance(&"bob".to_string()), 100);
     }
+
+    #[test]
+    fn transfer_zero_or_to_self() {
+        let mut balances = Pallet::<TestConfig>::new();
+        balances.set_balance_unchecked(&"alice".to_string(), 100);
+        assert_eq!(
+            balances.transfer("alice".to_string(), "bob".to_string(), 0),
+            Err(DispatchError::ZeroAmount)
+        );
+        assert_eq!(
+            balances.transfer("alice".to_string(), "alice".to_string(), 10),
+            Err(DispatchError::SelfTransfer)
+        );
+        assert_eq!(balances.balance(&"alice".to_string()), 100);
+        assert!(balances.events().is_empty());
+    }
+
+    #[test]
+    fn mint_balance() {
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(
+            balances.mint("alice".to_string(), "bob".to_string(), 100),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&"bob".to_string()), 100);
+        assert_eq!(
+            balances.mint("alice".to_string(), "bob".to_string(), u128::MAX),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.balance(&"bob".to_string()), 100);
+        assert_eq!(balances.total_issuance(), 100);
+    }
+
+    #[test]
+    fn total_issuance() {
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(balances.total_issuance(), 0);
+
+        assert_eq!(
+            balances.mint("alice".to_string(), "alice".to_string(), 70),
+            Ok(())
+        );
+        assert_eq!(
+            balances.mint("alice".to_string(), "bob".to_string(), 30),
+            Ok(())
+        );
+        assert_eq!(
+            balances.total_issuance(),
+            balances.balance(&"alice".to_string()) + balances.balance(&"bob".to_string())
+        );
+        assert_eq!(balances.total_issuance(), 100);
+
+        // A transfer moves tokens around, but does not change the total issuance.
+        assert_eq!(
+            balances.transfer("alice".to_string(), "bob".to_string(), 20),
+            Ok(())
+        );
+        assert_eq!(balances.total_issuance(), 100);
+    }
+
+    #[test]
+    fn burn_balance() {
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(
+            balances.mint("alice".to_string(), "alice".to_string(), 100),
+            Ok(())
+        );
+
+        // `alice` cannot burn more than she has.
+        assert_eq!(
+            balances.burn("alice".to_string(), 101),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(balances.balance(&"alice".to_string()), 100);
+        assert_eq!(balances.total_issuance(), 100);
+
+        assert_eq!(balances.burn("alice".to_string(), 40), Ok(()));
+        assert_eq!(balances.balance(&"alice".to_string()), 60);
+        assert_eq!(balances.total_issuance(), 60);
+    }
+
+    #[test]
+    fn withdraw_fee() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        assert_eq!(
+            balances.withdraw_fee(&alice, 101),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(balances.withdraw_fee(&alice, 5), Ok(()));
+        assert_eq!(balances.balance(&alice), 95);
+        assert_eq!(balances.total_issuance(), 95);
+
+        // Paying a fee can prune the account, removing its dust as well.
+        assert_eq!(balances.withdraw_fee(&alice, 90), Ok(()));
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.total_issuance(), 0);
+    }
+
+    #[test]
+    fn withdraw_fee_checks_frozen_and_locks() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let root = "root".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        // Fees cannot be paid with locked funds.
+        balances.set_lock(*b"staking ", &alice, 60);
+        assert_eq!(
+            balances.withdraw_fee(&alice, 50),
+            Err(DispatchError::BalanceLocked)
+        );
+        assert_eq!(balances.check_withdraw_fee(&alice, 40), Ok(60));
+        assert_eq!(balances.withdraw_fee(&alice, 40), Ok(()));
+        assert_eq!(balances.balance(&alice), 60);
+
+        // Frozen accounts cannot pay fees, unless they are zero.
+        assert_eq!(balances.freeze(root.clone(), alice.clone()), Ok(()));
+        assert_eq!(
+            balances.withdraw_fee(&alice, 1),
+            Err(DispatchError::AccountFrozen)
+        );
+        assert_eq!(balances.withdraw_fee(&alice, 0), Ok(()));
+        assert_eq!(balances.balance(&alice), 60);
+        assert_eq!(balances.total_issuance(), 60);
+    }
+
+    #[test]
+    fn withdraw_fee_to_recipient() {
+        let mut balances = Pallet::<TreasuryConfig>::new();
+        let alice = "alice".to_string();
+        let treasury = "treasury".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        // The fees are credited to the treasury, so the total issuance does not change.
+        assert_eq!(balances.withdraw_fee(&alice, 5), Ok(()));
+        assert_eq!(balances.balance(&alice), 95);
+        assert_eq!(balances.balance(&treasury), 5);
+        assert_eq!(balances.total_issuance(), 100);
+
+        // The treasury pays its own fees to itself.
+        assert_eq!(balances.withdraw_fee(&treasury, 5), Ok(()));
+        assert_eq!(balances.balance(&treasury), 5);
+
+        // Only the fee is credited to the treasury, the dust of a pruned account is still burned.
+        assert_eq!(balances.withdraw_fee(&alice, 90), Ok(()));
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.balance(&treasury), 95);
+        assert_eq!(balances.total_issuance(), 95);
+    }
+
+    #[test]
+    fn accrue_interest() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 1000), Ok(()));
+
+        // 5% interest, which is minted into the account.
+        assert_eq!(balances.accrue_interest(&alice, 5, 100), Ok(50));
+        assert_eq!(balances.balance(&alice), 1050);
+        assert_eq!(balances.total_issuance(), 1050);
+
+        // The interest is rounded down.
+        assert_eq!(balances.accrue_interest(&alice, 1, 1051), Ok(0));
+        assert_eq!(balances.balance(&alice), 1050);
+
+        assert_eq!(
+            balances.accrue_interest(&alice, 5, 0),
+            Err(DispatchError::DivisionByZero)
+        );
+        assert_eq!(balances.balance(&alice), 1050);
+    }
+
+    #[test]
+    fn accrue_interest_overflow() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, u128::MAX / 2), Ok(()));
+
+        // The multiplication by the numerator overflows, even though the rate is only 3%.
+        assert_eq!(
+            balances.accrue_interest(&alice, 3, 100),
+            Err(DispatchError::Overflow)
+        );
+        // Doubling the balance overflows as well.
+        assert_eq!(
+            balances.accrue_interest(&alice, 3, 2),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.balance(&alice), u128::MAX / 2);
+        assert_eq!(balances.total_issuance(), u128::MAX / 2);
+    }
+
+    #[test]
+    fn balance_history() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.balance_at(&alice, 0), None);
+
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+        balances.on_finalize(1);
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 30), Ok(()));
+        balances.on_finalize(2);
+
+        // A later transfer does not change the balances recorded at earlier blocks.
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 20), Ok(()));
+        assert_eq!(balances.balance_at(&alice, 1), Some(100));
+        assert_eq!(balances.balance_at(&bob, 1), Some(0));
+        assert_eq!(balances.balance_at(&alice, 2), Some(70));
+        assert_eq!(balances.balance_at(&bob, 2), Some(30));
+        assert_eq!(balances.balance_at(&alice, 3), None);
+
+        // Only the last 2 blocks are kept.
+        balances.on_finalize(3);
+        assert_eq!(balances.balance_at(&alice, 1), None);
+        assert_eq!(balances.balance_at(&alice, 2), Some(70));
+        assert_eq!(balances.balance_at(&alice, 3), Some(50));
+        assert_eq!(balances.balance_at(&bob, 3), Some(50));
+    }
+
+    #[test]
+    fn balance_history_with_empty_blocks() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+
+        // No account exists at the end of block 1, which is still part of the history.
+        balances.on_finalize(1);
+        assert_eq!(balances.balance_at(&alice, 1), Some(0));
+        assert_eq!(balances.balance_at(&alice, 2), None);
+
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+        balances.on_finalize(2);
+        assert_eq!(balances.balance_at(&alice, 1), Some(0));
+        assert_eq!(balances.balance_at(&alice, 2), Some(100));
+
+        // Block 3 is empty again, and block 1 falls out of the history.
+        assert_eq!(balances.set_balance_checked(&alice, 0), Ok(()));
+        balances.on_finalize(3);
+        assert_eq!(balances.balance_at(&alice, 1), None);
+        assert_eq!(balances.balance_at(&alice, 2), Some(100));
+        assert_eq!(balances.balance_at(&alice, 3), Some(0));
+        balances.on_finalize(4);
+        assert_eq!(balances.balance_at(&alice, 2), None);
+        assert_eq!(balances.balance_at(&alice, 3), Some(0));
+        assert_eq!(balances.balance_at(&alice, 4), Some(0));
+    }
+
+    #[test]
+    fn slash_is_capped_at_the_free_balance() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+        assert_eq!(balances.total_issuance(), 100);
+
+        let imbalance = balances.slash(&alice, 30);
+        assert_eq!(imbalance, Imbalance::Negative(30));
+        assert_eq!(balances.settle(imbalance), Ok(()));
+        assert_eq!(balances.balance(&alice), 70);
+        assert_eq!(balances.total_issuance(), 70);
+
+        // Only the available balance is slashed, and the account is pruned.
+        let imbalance = balances.slash(&alice, 500);
+        assert_eq!(imbalance.peek(), 70);
+        assert_eq!(balances.settle(imbalance), Ok(()));
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.total_issuance(), 0);
+        assert_eq!(balances.slash(&alice, 10), Imbalance::Negative(0));
+    }
+
+    #[test]
+    fn imbalances_are_settled_once() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+
+        // The total issuance only changes when the imbalance is settled, which consumes it.
+        let imbalance = balances.withdraw(&alice, 40).unwrap();
+        assert_eq!(balances.balance(&alice), 60);
+        assert_eq!(balances.total_issuance(), 100);
+        assert_eq!(balances.settle(imbalance), Ok(()));
+        assert_eq!(balances.total_issuance(), 60);
+
+        // Slashing alice to reward bob pairs off, so the total issuance does not change.
+        let slashed = balances.slash(&alice, 20);
+        let reward = balances.deposit(&bob, 15).unwrap();
+        let remaining = reward.offset(slashed).unwrap();
+        assert_eq!(remaining, Imbalance::Negative(5));
+        assert_eq!(balances.settle(remaining), Ok(()));
+        assert_eq!(balances.balance(&alice), 40);
+        assert_eq!(balances.balance(&bob), 15);
+        assert_eq!(balances.total_issuance(), 55);
+
+        assert_eq!(
+            Imbalance::Positive(u128::MAX).offset(Imbalance::Positive(1)),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(
+            balances.settle(Imbalance::Negative(1000)),
+            Err(DispatchError::Underflow)
+        );
+        assert_eq!(balances.total_issuance(), 55);
+    }
+
+    #[test]
+    fn transfer_all_balance() {
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(
+            balances.transfer_all("alice".to_string(), "bob".to_string()),
+            Err(DispatchError::InsufficientFunds)
+        );
+
+        balances.set_balance_unchecked(&"alice".to_string(), 100);
+        balances.set_balance_unchecked(&"bob".to_string(), 5);
+        assert_eq!(
+            balances.transfer_all("alice".to_string(), "bob".to_string()),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&"alice".to_string()), 0);
+        assert_eq!(balances.balance(&"bob".to_string()), 105);
+    }
+
+    #[test]
+    fn dust_removal() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        // `alice` keeps exactly the existential deposit, so she is not pruned.
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 90), Ok(()));
+        assert_eq!(
+            balances.balances.get(&alice).map(|data| data.free),
+            Some(10)
+        );
+
+        // `alice` drops 1 unit below the existential deposit, so she is pruned.
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 1), Ok(()));
+        assert_eq!(balances.balances.get(&alice), None);
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.balance(&bob), 91);
+        assert_eq!(balances.total_issuance(), 91);
+
+        // Burning `bob` down to zero prunes him as well.
+        assert_eq!(balances.burn(bob.clone(), 91), Ok(()));
+        assert_eq!(balances.balances.get(&bob), None);
+        assert_eq!(balances.total_issuance(), 0);
+    }
+
+    #[test]
+    fn reserve_and_unreserve() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        // `alice` cannot reserve more than her free balance.
+        assert_eq!(
+            balances.reserve(&alice, 101),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(balances.reserve(&alice, 60), Ok(()));
+        assert_eq!(balances.balance(&alice), 40);
+        assert_eq!(balances.reserved_balance(&alice), 60);
+        assert_eq!(balances.total_issuance(), 100);
+
+        // Reserved funds cannot be transferred.
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 50),
+            Err(DispatchError::InsufficientFunds)
+        );
+
+        // `alice` cannot unreserve more than she reserved.
+        assert_eq!(
+            balances.unreserve(&alice, 61),
+            Err(DispatchError::InsufficientReserved)
+        );
+        assert_eq!(balances.unreserve(&alice, 20), Ok(()));
+        assert_eq!(
+            balances.account(&alice),
+            AccountData {
+                free: 60,
+                reserved: 40
+            }
+        );
+    }
+
+    #[test]
+    fn reserved_balance_keeps_account_alive() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+        assert_eq!(balances.reserve(&alice, 50), Ok(()));
+
+        // The free balance is spent entirely, but the account still holds a reserved balance.
+        assert_eq!(balances.transfer_all(alice.clone(), bob.clone()), Ok(()));
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.reserved_balance(&alice), 50);
+        assert_eq!(balances.unreserve(&alice, 50), Ok(()));
+        assert_eq!(balances.balance(&alice), 50);
+        assert_eq!(balances.total_issuance(), 100);
+    }
+
+    #[test]
+    fn locked_funds_cannot_be_spent() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+        balances.set_lock(*b"staking ", &alice, 60);
+
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 50),
+            Err(DispatchError::BalanceLocked)
+        );
+        assert_eq!(
+            balances.burn(alice.clone(), 50),
+            Err(DispatchError::BalanceLocked)
+        );
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 40), Ok(()));
+        assert_eq!(balances.balance(&alice), 60);
+
+        // Locks overlap, so a smaller lock does not lock any more funds.
+        balances.set_lock(*b"voting  ", &alice, 30);
+        assert_eq!(balances.locked_balance(&alice), 60);
+
+        // After removing the larger lock, only the smaller lock applies.
+        balances.remove_lock(*b"staking ", &alice);
+        assert_eq!(balances.locked_balance(&alice), 30);
+        assert_eq!(balances.transfer_all(alice.clone(), bob.clone()), Ok(()));
+        assert_eq!(balances.balance(&alice), 30);
+        assert_eq!(balances.balance(&bob), 70);
+    }
+
+    #[test]
+    fn locked_accounts_are_not_pruned() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+        balances.set_lock(*b"staking ", &alice, 5);
+
+        // Spending down to the lock would prune `alice`, and remove her lock with her account.
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 95),
+            Err(DispatchError::WouldKillAccount)
+        );
+        assert_eq!(
+            balances.set_balance("root".to_string(), alice.clone(), 5),
+            Err(DispatchError::WouldKillAccount)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.total_issuance(), 100);
+
+        // Transferring everything keeps the existential deposit instead.
+        assert_eq!(balances.transfer_all(alice.clone(), bob.clone()), Ok(()));
+        assert_eq!(balances.balance(&alice), 10);
+        assert_eq!(balances.balance(&bob), 90);
+
+        // Slashes ignore locks, but still keep the existential deposit.
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 20), Ok(()));
+        let imbalance = balances.slash(&alice, 100);
+        assert_eq!(imbalance.peek(), 20);
+        assert_eq!(balances.settle(imbalance), Ok(()));
+        assert_eq!(balances.balance(&alice), 10);
+        assert_eq!(balances.locked_balance(&alice), 5);
+        assert_eq!(balances.total_issuance(), 100);
+
+        // Once the lock is removed, the account can be emptied.
+        balances.remove_lock(*b"staking ", &alice);
+        assert_eq!(balances.slash(&alice, 100), Imbalance::Negative(10));
+        assert_eq!(balances.balance(&alice), 0);
+    }
+
+    #[test]
+    fn can_transfer() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        balances.set_balance_unchecked(&alice, 100);
+        balances.set_balance_unchecked(&bob, u128::MAX - 10);
+
+        // `alice` does not have enough funds.
+        assert!(!balances.can_transfer(&alice, &bob, 101));
+        // The balance of `bob` would overflow.
+        assert!(!balances.can_transfer(&alice, &bob, 11));
+        assert!(balances.can_transfer(&alice, &bob, 10));
+
+        // Checking a transfer does not change any state.
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.balance(&bob), u128::MAX - 10);
+    }
+
+    #[test]
+    fn transfer_keep_alive() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.mint(alice.clone(), alice.clone(), 100), Ok(()));
+
+        // A normal transfer of 91 would leave `alice` with 9, below the existential deposit.
+        assert!(balances.can_transfer(&alice, &bob, 91));
+        assert_eq!(
+            balances.transfer_keep_alive(alice.clone(), bob.clone(), 91),
+            Err(DispatchError::WouldKillAccount)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+
+        // Keeping exactly the existential deposit is allowed.
+        assert_eq!(
+            balances.transfer_keep_alive(alice.clone(), bob.clone(), 90),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 10);
+        assert_eq!(balances.balance(&bob), 90);
+    }
+
+    #[test]
+    fn iterate_accounts() {
+        let mut balances = Pallet::<TestConfig>::new();
+        balances.set_balance_unchecked(&"charlie".to_string(), 300);
+        balances.set_balance_unchecked(&"alice".to_string(), 100);
+        balances.set_balance_unchecked(&"bob".to_string(), 200);
+
+        let accounts = balances.accounts().collect::<Vec<_>>();
+        assert_eq!(
+            accounts,
+            vec![
+                (&"alice".to_string(), &100),
+                (&"bob".to_string(), &200),
+                (&"charlie".to_string(), &300),
+            ]
+        );
+    }
+
+    #[test]
+    fn delegated_transfer() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let charlie = "charlie".to_string();
+        balances.set_balance_unchecked(&alice, 100);
+
+        // `bob` cannot spend on behalf of `alice` without an allowance.
+        assert_eq!(
+            balances.transfer_from(bob.clone(), alice.clone(), charlie.clone(), 10),
+            Err(DispatchError::InsufficientAllowance)
+        );
+
+        assert_eq!(balances.approve(alice.clone(), bob.clone(), 50), Ok(()));
+        assert_eq!(balances.allowance(&alice, &bob), 50);
+
+        // `bob` cannot spend more than his allowance.
+        assert_eq!(
+            balances.transfer_from(bob.clone(), alice.clone(), charlie.clone(), 51),
+            Err(DispatchError::InsufficientAllowance)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+
+        assert_eq!(
+            balances.transfer_from(bob.clone(), alice.clone(), charlie.clone(), 30),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 70);
+        assert_eq!(balances.balance(&bob), 0);
+        assert_eq!(balances.balance(&charlie), 30);
+        assert_eq!(balances.allowance(&alice, &bob), 20);
+    }
+
+    #[test]
+    fn transfer_events() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+        balances.take_events();
+
+        // A failed transfer does not emit an event.
+        assert!(balances.transfer(alice.clone(), bob.clone(), 200).is_err());
+        assert!(balances.events().is_empty());
+
+        // Funding a new account creates it.
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 30), Ok(()));
+        assert_eq!(
+            balances.events(),
+            &[
+                Event::Endowed {
+                    account: bob.clone()
+                },
+                Event::Transferred {
+                    from: alice.clone(),
+                    to: bob.clone(),
+                    amount: 30,
+                }
+            ]
+        );
+
+        assert_eq!(balances.transfer(bob.clone(), alice.clone(), 10), Ok(()));
+        let events = balances.take_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[2],
+            Event::Transferred {
+                from: bob.clone(),
+                to: alice.clone(),
+                amount: 10,
+            }
+        );
+        assert!(balances.events().is_empty());
+
+        // Dropping below the existential deposit reaps the account.
+        assert_eq!(balances.transfer(bob.clone(), alice.clone(), 15), Ok(()));
+        assert_eq!(
+            balances.events()[0],
+            Event::Reaped {
+                account: bob.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn set_balance_requires_root() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let root = "root".to_string();
+
+        assert_eq!(
+            balances.set_balance(alice.clone(), alice.clone(), 100),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.total_issuance(), 0);
+
+        assert_eq!(
+            balances.set_balance(root.clone(), alice.clone(), 100),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.total_issuance(), 100);
+
+        assert_eq!(
+            balances.set_balance(root.clone(), alice.clone(), 40),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 40);
+        assert_eq!(balances.total_issuance(), 40);
+
+        // Balances below the existential deposit are removed from the total issuance as well.
+        assert_eq!(balances.set_balance(root.clone(), alice.clone(), 5), Ok(()));
+        assert_eq!(balances.balance(&alice), 0);
+        assert_eq!(balances.total_issuance(), 0);
+    }
+
+    #[test]
+    fn try_transfer_never_panics() {
+        // A small xorshift generator, so the test is random-looking but reproducible.
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        // Mix small, large and extreme values to hit the edges of the checked math.
+        let mut random_u128 = move || match next() % 4 {
+            0 => (next() % 200) as u128,
+            1 => u128::MAX - (next() % 200) as u128,
+            _ => ((next() as u128) << 64) | next() as u128,
+        };
+
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        for _ in 0..1000 {
+            let mut balances = Pallet::<TestConfig>::new();
+            let (alice_balance, bob_balance, amount) =
+                (random_u128(), random_u128(), random_u128());
+            if balances.set_balance_checked(&alice, alice_balance).is_err()
+                || balances.set_balance_checked(&bob, bob_balance).is_err()
+            {
+                continue;
+            }
+            let (alice_before, bob_before) = (balances.balance(&alice), balances.balance(&bob));
+            let issuance_before = balances.total_issuance();
+
+            match balances.try_transfer(&alice, &bob, amount) {
+                Ok(()) => {
+                    // The amount moves to bob, and only the dust of a pruned sender is burned.
+                    assert_eq!(balances.balance(&bob), bob_before + amount);
+                    let dust = alice_before - amount - balances.balance(&alice);
+                    assert_eq!(balances.total_issuance(), issuance_before - dust);
+                    assert_eq!(
+                        balances.total_issuance(),
+                        balances.balance(&alice) + balances.balance(&bob)
+                    );
+                }
+                Err(_) => {
+                    assert_eq!(balances.balance(&alice), alice_before);
+                    assert_eq!(balances.balance(&bob), bob_before);
+                    assert_eq!(balances.total_issuance(), issuance_before);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transfer_assets() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+
+        // Like the native asset, any account can mint other assets.
+        assert_eq!(
+            balances.mint_asset(alice.clone(), 1, alice.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(
+            balances.mint_asset(bob.clone(), 2, alice.clone(), 70),
+            Ok(())
+        );
+
+        // Each asset is transferred independently of the others.
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 1, bob.clone(), 20),
+            Ok(())
+        );
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 2, bob.clone(), 5),
+            Ok(())
+        );
+        assert_eq!(balances.asset_balance(&1, &alice), 30);
+        assert_eq!(balances.asset_balance(&1, &bob), 20);
+        assert_eq!(balances.asset_balance(&2, &alice), 65);
+        assert_eq!(balances.asset_balance(&2, &bob), 5);
+
+        // Failed transfers of one asset do not touch another.
+        assert_eq!(
+            balances.transfer_asset(bob.clone(), 2, alice.clone(), 6),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(
+            balances.transfer_asset(bob.clone(), 1, alice.clone(), 20),
+            Ok(())
+        );
+        assert_eq!(balances.asset_balance(&1, &bob), 0);
+        assert_eq!(balances.asset_balance(&2, &bob), 5);
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 3, bob.clone(), 1),
+            Err(DispatchError::InsufficientFunds)
+        );
+
+        // The other assets do not change the native balances, which use the native asset id.
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.total_issuance(), 100);
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 0, bob.clone(), 40),
+            Ok(())
+        );
+        assert_eq!(balances.asset_balance(&0, &alice), 60);
+        assert_eq!(balances.balance(&bob), 40);
+        assert_eq!(balances.asset_balance(&1, &alice), 50);
+    }
+
+    #[test]
+    fn minimum_transfer() {
+        let mut balances = Pallet::<MinTransferConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        balances.set_balance_unchecked(&alice, 100);
+
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 0),
+            Err(DispatchError::ZeroAmount)
+        );
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 4),
+            Err(DispatchError::BelowMinimumTransfer)
+        );
+        assert!(!balances.can_transfer(&alice, &bob, 4));
+        assert_eq!(balances.balance(&alice), 100);
+
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 5), Ok(()));
+        assert_eq!(balances.balance(&alice), 95);
+        assert_eq!(balances.balance(&bob), 5);
+    }
+
+    #[test]
+    fn set_balance_checked_keeps_issuance_consistent() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let total = |balances: &Pallet<TestConfig>| {
+            balances
+                .balances
+                .values()
+                .map(|data| data.free + data.reserved)
+                .sum::<u128>()
+        };
+
+        assert_eq!(balances.set_balance_checked(&bob, 50), Ok(()));
+        assert_eq!(balances.reserve(&bob, 20), Ok(()));
+        for amount in [100, 30, 500, 0, 11, 9, 1000, 10] {
+            assert_eq!(balances.set_balance_checked(&alice, amount), Ok(()));
+            assert_eq!(balances.total_issuance(), total(&balances));
+        }
+        assert_eq!(balances.balance(&alice), 10);
+        assert_eq!(balances.total_issuance(), 60);
+
+        // Overflowing the total issuance fails without changing any balance.
+        assert_eq!(
+            balances.set_balance_checked(&alice, u128::MAX),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.balance(&alice), 10);
+        assert_eq!(balances.total_issuance(), 60);
+    }
+
+    #[test]
+    fn call_names() {
+        assert_eq!(
+            super::Call::<TestConfig>::call_names(),
+            &[
+                "transfer",
+                "transfer_keep_alive",
+                "transfer_many",
+                "force_transfer",
+                "set_balance",
+                "transfer_all",
+                "approve",
+                "transfer_from",
+                "mint",
+                "burn",
+                "transfer_asset",
+                "mint_asset",
+                "freeze",
+                "thaw"
+            ]
+        );
+    }
+
+    #[test]
+    fn force_transfer_requires_root() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        balances.set_balance_unchecked(&alice, 100);
+
+        assert_eq!(
+            balances.force_transfer(bob.clone(), alice.clone(), bob.clone(), 50),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+
+        assert_eq!(
+            balances.force_transfer("root".to_string(), alice.clone(), bob.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 50);
+        assert_eq!(balances.balance(&bob), 50);
+    }
+
+    #[test]
+    fn transfer_to_many_recipients() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let charlie = "charlie".to_string();
+        assert_eq!(balances.set_balance_checked(&alice, 100), Ok(()));
+        balances.take_events();
+
+        // The total exceeds the balance of `alice`, so nothing is transferred.
+        assert_eq!(
+            balances.transfer_many(
+                alice.clone(),
+                vec![(bob.clone(), 60), (charlie.clone(), 50)]
+            ),
+            Err(DispatchError::InsufficientFunds)
+        );
+        // Every payout is checked like a transfer, so a single payout which `alice` cannot afford
+        // is rejected as well.
+        assert_eq!(
+            balances.transfer_many(
+                alice.clone(),
+                vec![(bob.clone(), 60), (charlie.clone(), u128::MAX)]
+            ),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.balance(&bob), 0);
+        assert_eq!(balances.balance(&charlie), 0);
+        assert!(balances.take_events().is_empty());
+
+        // Recipients can be paid more than once.
+        assert_eq!(
+            balances.transfer_many(
+                alice.clone(),
+                vec![(bob.clone(), 20), (charlie.clone(), 30), (bob.clone(), 10)]
+            ),
+            Ok(())
+        );
+        assert_eq!(balances.balance(&alice), 40);
+        assert_eq!(balances.balance(&bob), 30);
+        assert_eq!(balances.balance(&charlie), 30);
+        assert_eq!(balances.total_issuance(), 100);
+
+        // Each payout is affordable on its own, but the total overflows, so nothing is transferred.
+        let mut balances = Pallet::<TestConfig>::new();
+        assert_eq!(balances.set_balance_checked(&alice, u128::MAX), Ok(()));
+        assert_eq!(
+            balances.transfer_many(
+                alice.clone(),
+                vec![(bob.clone(), u128::MAX), (charlie.clone(), 1)]
+            ),
+            Err(DispatchError::Overflow)
+        );
+        assert_eq!(balances.balance(&alice), u128::MAX);
+        assert_eq!(balances.balance(&bob), 0);
+    }
+
+    #[test]
+    fn frozen_accounts_cannot_transfer() {
+        let mut balances = Pallet::<TestConfig>::new();
+        let root = "root".to_string();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        balances.set_balance_unchecked(&alice, 100);
+        balances.set_balance_unchecked(&bob, 100);
+
+        assert_eq!(
+            balances.freeze(alice.clone(), alice.clone()),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(balances.freeze(root.clone(), alice.clone()), Ok(()));
+        assert!(balances.is_frozen(&alice));
+
+        // A frozen account can neither send nor receive funds.
+        assert_eq!(
+            balances.transfer(alice.clone(), bob.clone(), 10),
+            Err(DispatchError::AccountFrozen)
+        );
+        assert_eq!(
+            balances.transfer(bob.clone(), alice.clone(), 10),
+            Err(DispatchError::AccountFrozen)
+        );
+        assert_eq!(balances.balance(&alice), 100);
+        assert_eq!(balances.balance(&bob), 100);
+
+        // The same goes for the other assets.
+        assert_eq!(
+            balances.mint_asset(root.clone(), 1, alice.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(
+            balances.mint_asset(root.clone(), 1, bob.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 1, bob.clone(), 10),
+            Err(DispatchError::AccountFrozen)
+        );
+        assert_eq!(
+            balances.transfer_asset(bob.clone(), 1, alice.clone(), 10),
+            Err(DispatchError::AccountFrozen)
+        );
+        assert_eq!(balances.asset_balance(&1, &alice), 50);
+        assert_eq!(balances.asset_balance(&1, &bob), 50);
+
+        assert_eq!(
+            balances.thaw(bob.clone(), alice.clone()),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(balances.thaw(root, alice.clone()), Ok(()));
+        assert!(!balances.is_frozen(&alice));
+
+        assert_eq!(balances.transfer(alice.clone(), bob.clone(), 10), Ok(()));
+        assert_eq!(balances.transfer(bob.clone(), alice.clone(), 20), Ok(()));
+        assert_eq!(balances.balance(&alice), 110);
+        assert_eq!(balances.balance(&bob), 90);
+        assert_eq!(
+            balances.transfer_asset(alice.clone(), 1, bob.clone(), 10),
+            Ok(())
+        );
+        assert_eq!(balances.asset_balance(&1, &bob), 60);
+    }
 }
@@ -0,0 +1,15 @@
+//! The core of our simple state machine: the pallets, and the support types they share.
+//!
+//! The library is `no_std` compatible, so it can be built for embedded or wasm targets, as long as
+//! an allocator is available through `alloc`. The standard library is only linked with the `std`
+//! feature, which is enabled by default. The `Runtime` which puts the pallets together lives in
+//! the `main` binary, which always uses `std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod balances;
+pub mod proof_of_existence;
+pub mod support;
+pub mod system;
+pub mod timestamp;
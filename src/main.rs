@@ -1,7 +1,5 @@
-mod balances;
-mod proof_of_existence;
-mod support;
-mod system;
+// The pallets are implemented in the library of this crate.
+use rust_state_machine::{balances, proof_of_existence, support, system, timestamp};
 
 use crate::support::Dispatch;
 
@@ -13,77 +11,2871 @@ mod types {
     pub type Balance = u128;
     pub type BlockNumber = u32;
     pub type Nonce = u32;
+    pub type Hash = u64;
+    pub type Hashing = crate::support::DefaultHashing;
     pub type Block = crate::support::Block<Header, Extrinsic>;
-    pub type Header = crate::support::Header<BlockNumber>;
-    pub type Extrinsic = crate::support::Extrinsic<AccountId, crate::RuntimeCall>;
-    pub type Content = &'static str;
+    pub type Header = crate::support::Header<BlockNumber, Hash>;
+    pub type Signature = crate::support::MockSignature;
+    pub type Extrinsic =
+        crate::support::Extrinsic<AccountId, crate::RuntimeCall, Nonce, Signature, Balance>;
+    pub type BlockBuilder = crate::support::BlockBuilder<
+        BlockNumber,
+        Hash,
+        AccountId,
+        crate::RuntimeCall,
+        Nonce,
+        Balance,
+    >;
+    pub type Content = String;
+    pub type AssetId = u32;
+    pub type Moment = u64;
 }
 
 // This is our main Runtime.
 // It accumulates all of the different pallets we want to use.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[macros::runtime]
 pub struct Runtime {
     system: system::Pallet<Self>,
     balances: balances::Pallet<Self>,
     proof_of_existence: proof_of_existence::Pallet<Self>,
+    timestamp: timestamp::Pallet<Self>,
 }
 
 impl balances::Config for Runtime {
     type Balance = types::Balance;
+    const EXISTENTIAL_DEPOSIT: types::Balance = 1;
+    const MIN_TRANSFER: types::Balance = 1;
+    type AssetId = types::AssetId;
+    const NATIVE_ASSET: types::AssetId = 0;
+    // Fees are paid to the treasury, instead of being burned.
+    fn fee_recipient() -> Option<types::AccountId> {
+        Some("treasury".to_string())
+    }
+    // The balances of the last 10 blocks are kept for auditing.
+    const HISTORY_DEPTH: Option<types::BlockNumber> = Some(10);
 }
 
 impl system::Config for Runtime {
     type AccountId = types::AccountId;
     type BlockNumber = types::BlockNumber;
     type Nonce = types::Nonce;
+    type RuntimeEvent = RuntimeEvent;
+    type Hash = types::Hash;
+    type Hashing = types::Hashing;
+    type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+    const MAX_BLOCK_WEIGHT: support::Weight = support::Weight(100);
+    fn root() -> types::AccountId {
+        "root".to_string()
+    }
 }
 
 impl proof_of_existence::Config for Runtime {
     type Content = types::Content;
+    type ContentHash = types::Hash;
+    type Hasher = types::Hashing;
+    const MAX_CLAIMS: u32 = 100;
+    type Metadata = String;
+    const MAX_CONTENT_LEN: usize = 1024;
+    const EXPIRY_PERIOD: Option<types::BlockNumber> = None;
+}
+
+impl timestamp::Config for Runtime {
+    type Moment = types::Moment;
+}
+
+// Every extrinsic costs one token per 10 units of weight, plus its optional tip, which are paid to
+// the treasury.
+impl support::ChargeFee for Runtime {
+    type Balance = types::Balance;
+
+    fn fee(call: &RuntimeCall, tip: &Option<types::Balance>) -> types::Balance {
+        let base_fee: types::Balance = (support::GetWeight::weight(call).0 / 10).into();
+        base_fee.saturating_add(tip.unwrap_or(0))
+    }
+
+    fn charge_fee(
+        &mut self,
+        who: &types::AccountId,
+        call: &RuntimeCall,
+        tip: &Option<types::Balance>,
+    ) -> support::DispatchResult {
+        support::ChargeFee::can_pay_fee(self, who, call, tip)?;
+        self.balances.withdraw_fee(who, Self::fee(call, tip))
+    }
+
+    fn can_pay_fee(
+        &self,
+        who: &types::AccountId,
+        call: &RuntimeCall,
+        tip: &Option<types::Balance>,
+    ) -> support::DispatchResult {
+        let fee = Self::fee(call, tip);
+        let balance = self.balances.balance(who);
+        if balance < fee {
+            return Err(support::DispatchError::InsufficientFunds);
+        }
+        // Accounts with consumers must keep their existential deposit after paying the fee. This is
+        // also enforced by their consumer lock, but this error is more precise.
+        if self.system.consumers(who) > 0
+            && balance - fee < <Self as balances::Config>::EXISTENTIAL_DEPOSIT
+        {
+            return Err(support::DispatchError::WouldKillAccount);
+        }
+        // Fees are subject to the same frozen and lock checks as transfers.
+        self.balances.check_withdraw_fee(who, fee).map(|_| ())
+    }
+}
+
+impl Runtime {
+    // Create a new runtime whose chain starts at `block_number` instead of zero, for example to
+    // continue from forked state. The first block to execute is then `block_number + 1`.
+    #[allow(dead_code)]
+    fn new_at(block_number: types::BlockNumber) -> Self {
+        let mut runtime = Self::new();
+        runtime.system.set_block_number(block_number);
+        runtime
+    }
+}
+
+// The balances pallet provides for the existence of accounts: an account is created in the system
+// pallet when it is funded, and removed when its balance is reaped.
+//
+// The proof of existence pallet consumes accounts: every claim adds a consumer to its owner. While
+// an account has consumers, its existential deposit is locked, so it cannot be reaped by spending
+// its balance.
+impl support::OnEvent<RuntimeEvent> for Runtime {
+    fn on_event(&mut self, event: &RuntimeEvent) {
+        match event {
+            RuntimeEvent::balances(balances::Event::Endowed { account }) => {
+                self.system.inc_providers(account)
+            }
+            RuntimeEvent::balances(balances::Event::Reaped { account }) => {
+                self.system.dec_providers(account)
+            }
+            RuntimeEvent::proof_of_existence(proof_of_existence::Event::ClaimCreated {
+                owner,
+                ..
+            }) => self.inc_consumers(owner),
+            RuntimeEvent::proof_of_existence(proof_of_existence::Event::ClaimRevoked {
+                owner,
+                ..
+            }) => self.dec_consumers(owner),
+            RuntimeEvent::proof_of_existence(proof_of_existence::Event::ClaimsRevoked {
+                owner,
+                count,
+            }) => {
+                for _ in 0..*count {
+                    self.dec_consumers(owner);
+                }
+            }
+            RuntimeEvent::proof_of_existence(proof_of_existence::Event::ClaimTransferred {
+                from,
+                to,
+                ..
+            }) => {
+                self.dec_consumers(from);
+                self.inc_consumers(to);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Claims can only be created for, or transferred to, accounts which exist.
+impl support::CheckCall for Runtime {
+    fn check_call(
+        &self,
+        origin: &support::Origin<types::AccountId>,
+        call: &RuntimeCall,
+    ) -> support::DispatchResult {
+        let owner = match (origin, call) {
+            (
+                support::Origin::Signed(caller),
+                RuntimeCall::proof_of_existence(
+                    proof_of_existence::Call::create_claim { .. }
+                    | proof_of_existence::Call::create_claim_with_metadata { .. }
+                    | proof_of_existence::Call::create_claims { .. },
+                ),
+            ) => caller,
+            (
+                _,
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::transfer_claim {
+                    new_owner,
+                    ..
+                }),
+            ) => new_owner,
+            _ => return Ok(()),
+        };
+        if !self.system.account_exists(owner) {
+            return Err(support::DispatchError::NoProviders);
+        }
+        Ok(())
+    }
+}
+
+impl Runtime {
+    // The lock which keeps the existential deposit of an account with consumers from being spent.
+    const CONSUMER_LOCK: balances::LockId = *b"consumer";
+
+    // Add a consumer to `who`, locking its existential deposit. Claims are only created for
+    // accounts which exist, so this only fails if the owner of a claim was reaped by the same
+    // extrinsic, in which case there is nothing left to lock.
+    fn inc_consumers(&mut self, who: &types::AccountId) {
+        if self.system.inc_consumers(who).is_ok() {
+            self.balances.set_lock(
+                Self::CONSUMER_LOCK,
+                who,
+                <Self as balances::Config>::EXISTENTIAL_DEPOSIT,
+            );
+        }
+    }
+
+    // Remove a consumer from `who`, unlocking its existential deposit once it has none left.
+    fn dec_consumers(&mut self, who: &types::AccountId) {
+        self.system.dec_consumers(who);
+        if self.system.consumers(who) == 0 {
+            self.balances.remove_lock(Self::CONSUMER_LOCK, who);
+        }
+    }
+}
+
+impl Runtime {
+    // Remove the account `who` from the runtime: its balance is burned, and its nonce is reset.
+    //
+    // Note that resetting the nonce means old extrinsics of `who` could be replayed, if the
+    // account is funded again.
+    //
+    // Accounts with consumers, like claim owners, cannot be removed.
+    #[allow(dead_code)]
+    fn kill_account(&mut self, who: &types::AccountId) -> support::DispatchResult {
+        if self.system.consumers(who) > 0 {
+            return Err(support::DispatchError::WouldKillAccount);
+        }
+        self.balances.kill_account(who)?;
+        self.system.kill_account(who);
+        Ok(())
+    }
+}
+
+// A read-only query API, which lets external tools read the state of the runtime without
+// depending on the internals of each pallet. The demo in `main` does not need all of it.
+#[allow(dead_code)]
+impl Runtime {
+    // Get the balance of the account `who`.
+    fn balance_of(&self, who: &types::AccountId) -> types::Balance {
+        self.balances.balance(who)
+    }
+
+    // Get the nonce of the account `who`, which is the nonce expected by its next extrinsic.
+    fn nonce_of(&self, who: &types::AccountId) -> types::Nonce {
+        self.system.nonce(who)
+    }
+
+    // Get the owner (if any) of a claim on `content`.
+    fn claim_owner(&self, content: &types::Content) -> Option<&types::AccountId> {
+        self.proof_of_existence.get_claim(content)
+    }
+
+    // Get the current block number, which is the number of the last executed block.
+    fn block_number(&self) -> types::BlockNumber {
+        self.system.block_number()
+    }
+
+    // Get the current time, as set by the timestamp pallet.
+    fn current_time(&self) -> types::Moment {
+        self.timestamp.now()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[allow(dead_code)]
+impl Runtime {
+    // Export the state of all the pallets as JSON.
+    //
+    // Note that events are not part of the exported state, since they only live for one block.
+    fn export_state(&self) -> String {
+        serde_json::to_string(self).expect("runtime state is always serializable")
+    }
+
+    // Replace the state of all the pallets with a state previously exported by `export_state`.
+    fn import_state(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_str(json)?;
+        Ok(())
+    }
+}
+
+// What to do when a block fails to execute in `run_blocks`. The demo in `main` only stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+enum OnBlockError {
+    // Panic with the error, like `execute_block(..).expect(..)` would.
+    Panic,
+    // Stop executing blocks, and return the outcomes so far.
+    Stop,
+    // Keep executing the remaining blocks.
+    Continue,
+}
+
+// The outcome of executing a single block in `run_blocks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlockOutcome {
+    block_number: types::BlockNumber,
+    result: Result<(), support::BlockError<types::AccountId>>,
+}
+
+// Execute `blocks` in order, and return the outcome of every executed block. The error of a
+// failing block tells which extrinsic failed and why, and `on_error` decides whether the
+// remaining blocks are still executed.
+//
+// Every block is executed atomically, so a failing block is rolled back and the runtime stays at
+// the last successful block. With `Continue`, the next blocks must be built on top of that block,
+// like a replacement for the failing block.
+fn run_blocks(
+    runtime: &mut Runtime,
+    blocks: Vec<types::Block>,
+    on_error: OnBlockError,
+) -> Vec<BlockOutcome> {
+    let mut outcomes = Vec::new();
+    for block in blocks {
+        let block_number = block.header.block_number;
+        let result = runtime.execute_block_atomic(block);
+        let failed = result.is_err();
+        if let (Err(error), OnBlockError::Panic) = (&result, on_error) {
+            panic!("invalid block {}: {}", block_number, error);
+        }
+        outcomes.push(BlockOutcome {
+            block_number,
+            result,
+        });
+        if failed && on_error == OnBlockError::Stop {
+            break;
+        }
+    }
+    outcomes
 }
 
 fn main() {
-    let mut runtime = Runtime::new();
     let alice = "alice".to_string();
     let bob = "bob".to_string();
     let charlie = "charlie".to_string();
 
     // this would happen in block 0
-    runtime.balances.set_balance(&alice, 100);
+    let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+        balances: balances::GenesisConfig {
+            balances: vec![(alice.clone(), 100)],
+        },
+        ..Default::default()
+    });
+
+    let block_1 = types::BlockBuilder::new(1)
+        .push(
+            alice.clone(),
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount: 30,
+            }),
+        )
+        .push(
+            alice.clone(),
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                claim: "blablub".to_string(),
+            }),
+        )
+        .build();
+
+    let block_2 = types::BlockBuilder::new(2)
+        .parent_hash(block_1.header.hash::<types::Hashing>())
+        .nonce(alice.clone(), 2)
+        .push(
+            alice.clone(),
+            RuntimeCall::balances(balances::Call::transfer {
+                to: charlie.clone(),
+                amount: 20,
+            }),
+        )
+        .build();
+
+    let outcomes = run_blocks(&mut runtime, vec![block_1, block_2], OnBlockError::Stop);
+
+    println!("{:#?}", runtime);
 
-    let block_1 = types::Block {
-        header: support::Header { block_number: 1 },
-        extrinsics: vec![
-            support::Extrinsic {
+    // Failing blocks are reported with their block number, and the position of the failing
+    // extrinsic, instead of crashing the demo.
+    let mut failed = false;
+    for outcome in outcomes {
+        if let Err(error) = outcome.result {
+            eprintln!("block {} failed: {}", outcome.block_number, error);
+            failed = true;
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        balances, proof_of_existence, run_blocks,
+        support::{self, BlockError, Dispatch, DispatchError},
+        system, timestamp, types, BlockOutcome, OnBlockError, Runtime, RuntimeCall, RuntimeEvent,
+        RuntimeGenesisConfig,
+    };
+
+    #[test]
+    fn mint_through_block() {
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 10)],
+            },
+            ..Default::default()
+        });
+        let bob = "bob".to_string();
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::mint {
+                    to: bob.clone(),
+                    amount: 50,
+                }),
+            )],
+        };
+
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 50);
+        // `alice` paid the fee of the mint.
+        assert_eq!(runtime.balances.balance(&alice), 9);
+    }
+
+    #[test]
+    fn burn_through_block() {
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 10)],
+            },
+            ..Default::default()
+        });
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![
+                support::Extrinsic::new_signed(
+                    alice.clone(),
+                    0,
+                    RuntimeCall::balances(balances::Call::mint {
+                        to: alice.clone(),
+                        amount: 50,
+                    }),
+                ),
+                support::Extrinsic::new_signed(
+                    alice.clone(),
+                    1,
+                    RuntimeCall::balances(balances::Call::burn { amount: 20 }),
+                ),
+            ],
+        };
+
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        // `alice` paid a fee of 1 for each extrinsic to the treasury.
+        assert_eq!(runtime.balances.balance(&alice), 38);
+        assert_eq!(runtime.balances.balance(&"treasury".to_string()), 2);
+        assert_eq!(runtime.balances.total_issuance(), 40);
+    }
+
+    #[test]
+    fn stale_nonce_is_rejected() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        let transfer = |nonce| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 10,
+                }),
+            )
+        };
+
+        // The second extrinsic replays the first one, and must be rejected.
+        let block_1 = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![transfer(0), transfer(0)],
+        };
+        assert_eq!(
+            runtime.execute_block(block_1),
+            Err(BlockError::ExtrinsicFailed {
+                index: 1,
                 caller: alice.clone(),
-                call: RuntimeCall::balances(balances::Call::transfer {
+                pallet: None,
+                error: DispatchError::InvalidNonce,
+            })
+        );
+        assert_eq!(runtime.balances.balance(&alice), 89);
+        assert_eq!(runtime.balances.balance(&bob), 10);
+
+        let block_2 = types::Block {
+            header: support::Header {
+                block_number: 2,
+                parent_hash: runtime.system.block_hash(1).unwrap(),
+            },
+            extrinsics: vec![transfer(1)],
+        };
+        assert_eq!(runtime.execute_block(block_2), Ok(()));
+        assert_eq!(runtime.balances.balance(&alice), 78);
+        assert_eq!(runtime.balances.balance(&bob), 20);
+        assert_eq!(runtime.system.nonce(&alice), 2);
+    }
+
+    #[test]
+    fn failing_extrinsic_is_reported() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        let transfer = |nonce, amount| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount,
+                }),
+            )
+        };
+
+        // The second transfer overdraws the account of `alice`.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![transfer(0, 60), transfer(1, 60)],
+        };
+        let error = runtime.execute_block(block).unwrap_err();
+        assert_eq!(
+            error,
+            BlockError::ExtrinsicFailed {
+                index: 1,
+                caller: alice.clone(),
+                pallet: Some("balances"),
+                error: DispatchError::InsufficientFunds,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Extrinsic 1 by alice failed in balances: Not enough funds"
+        );
+    }
+
+    #[test]
+    fn transfer_claim_through_block() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 10), (bob.clone(), 10)],
+            },
+            ..Default::default()
+        });
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![
+                support::Extrinsic::new_signed(
+                    alice.clone(),
+                    0,
+                    RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                        claim: "document".to_string(),
+                    }),
+                ),
+                support::Extrinsic::new_signed(
+                    alice.clone(),
+                    1,
+                    RuntimeCall::proof_of_existence(proof_of_existence::Call::transfer_claim {
+                        claim: "document".to_string(),
+                        new_owner: bob.clone(),
+                    }),
+                ),
+            ],
+        };
+
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(
+            runtime
+                .proof_of_existence
+                .get_claim(&"document".to_string()),
+            Some(&bob)
+        );
+        let info = runtime
+            .proof_of_existence
+            .get_claim_info(&"document".to_string());
+        assert_eq!(info.unwrap().created_at, 1);
+
+        // The claim moved its consumer from alice to bob.
+        assert_eq!(runtime.system.consumers(&alice), 0);
+        assert_eq!(runtime.system.consumers(&bob), 1);
+    }
+
+    #[test]
+    fn claims_need_existing_accounts() {
+        let alice = "alice".to_string();
+        let carol = "carol".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let create_claim = || {
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                claim: "document".to_string(),
+            })
+        };
+
+        assert_eq!(
+            runtime.apply_extrinsic(support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                create_claim()
+            )),
+            Ok(())
+        );
+        assert_eq!(runtime.system.consumers(&alice), 1);
+
+        // Carol was never funded, so carol does not exist and cannot receive the claim.
+        let transfer_claim = || {
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::transfer_claim {
+                claim: "document".to_string(),
+                new_owner: carol.clone(),
+            })
+        };
+        assert_eq!(
+            runtime.apply_extrinsic(support::Extrinsic::new_signed(
+                alice.clone(),
+                1,
+                transfer_claim()
+            )),
+            Err(DispatchError::NoProviders)
+        );
+        assert_eq!(
+            runtime.dispatch(
+                support::Origin::Signed(alice.clone()),
+                RuntimeCall::batch(vec![transfer_claim()])
+            ),
+            Err(DispatchError::BatchInterrupted {
+                index: 0,
+                error: Box::new(DispatchError::NoProviders),
+            })
+        );
+        assert_eq!(runtime.claim_owner(&"document".to_string()), Some(&alice));
+        assert_eq!(runtime.system.consumers(&carol), 0);
+
+        // Nor can carol create claims.
+        assert_eq!(
+            runtime.dispatch(support::Origin::Signed(carol.clone()), create_claim()),
+            Err(DispatchError::NoProviders)
+        );
+    }
+
+    #[test]
+    fn claim_owners_cannot_be_reaped() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let transfer = |amount| {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount,
+            })
+        };
+        let revoke_claim = || {
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::revoke_claim {
+                claim: "document".to_string(),
+            })
+        };
+
+        // Creating the claim costs a fee of 1.
+        assert_eq!(
+            runtime.apply_extrinsic(support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: "document".to_string(),
+                }),
+            )),
+            Ok(())
+        );
+        assert_eq!(runtime.balance_of(&alice), 99);
+
+        // Alice holds a claim, so the existential deposit of alice cannot be spent, not even by
+        // fees, and the account cannot be removed.
+        let alice_origin = || support::Origin::Signed(alice.clone());
+        assert_eq!(
+            runtime.dispatch(alice_origin(), transfer(99)),
+            Err(DispatchError::BalanceLocked)
+        );
+        assert_eq!(
+            runtime.kill_account(&alice),
+            Err(DispatchError::WouldKillAccount)
+        );
+        assert!(runtime.dispatch(alice_origin(), transfer(97)).is_ok());
+        assert_eq!(
+            runtime.apply_extrinsic(support::Extrinsic::new_signed_with_tip(
+                alice.clone(),
+                1,
+                revoke_claim(),
+                Some(1),
+            )),
+            Err(DispatchError::WouldKillAccount)
+        );
+        assert_eq!(runtime.nonce_of(&alice), 1);
+        assert!(runtime.system.account_exists(&alice));
+
+        // Once the claim is revoked, alice can spend everything.
+        assert_eq!(
+            runtime.apply_extrinsic(support::Extrinsic::new_signed(
+                alice.clone(),
+                1,
+                revoke_claim()
+            )),
+            Ok(())
+        );
+        assert_eq!(runtime.system.consumers(&alice), 0);
+        assert!(runtime.dispatch(alice_origin(), transfer(1)).is_ok());
+        assert_eq!(runtime.balance_of(&alice), 0);
+    }
+
+    #[test]
+    fn set_timestamp_through_block() {
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 10)],
+            },
+            ..Default::default()
+        });
+        let set = |nonce, now| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::timestamp(timestamp::Call::set { now }),
+            )
+        };
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![set(0, 1000), set(1, 500)],
+        };
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 1,
+                caller: alice.clone(),
+                pallet: Some("timestamp"),
+                error: DispatchError::TimestampNotIncreasing,
+            })
+        );
+        assert_eq!(runtime.timestamp.now(), 1000);
+    }
+
+    #[test]
+    fn block_collects_runtime_events() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        // The treasury already exists, so the fees do not create it.
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), ("treasury".to_string(), 1)],
+            },
+            ..Default::default()
+        });
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![
+                support::Extrinsic::new_signed(
+                    alice.clone(),
+                    0,
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 30,
+                    }),
+                ),
+                support::Extrinsic::new_signed(
+                    bob.clone(),
+                    0,
+                    RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                        claim: "document".to_string(),
+                    }),
+                ),
+            ],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.system.extrinsic_count(), 2);
+
+        let events = runtime.system.events();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            &events[0],
+            RuntimeEvent::balances(balances::Event::Endowed { account }) if account == &bob
+        ));
+        assert!(matches!(
+            &events[1],
+            RuntimeEvent::balances(balances::Event::Transferred { from, to, amount: 30 })
+                if from == &alice && to == &bob
+        ));
+        assert!(matches!(
+            &events[2],
+            RuntimeEvent::system(system::Event::NewAccount { who }) if who == &bob
+        ));
+        assert!(matches!(
+            &events[3],
+            RuntimeEvent::proof_of_existence(proof_of_existence::Event::ClaimCreated {
+                owner,
+                claim,
+            }) if owner == &bob && claim == "document"
+        ));
+
+        // The events are stored per block.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 2,
+                parent_hash: runtime.system.block_hash(1).unwrap(),
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert!(runtime.system.events().is_empty());
+        assert_eq!(runtime.system.extrinsic_count(), 0);
+    }
+
+    #[test]
+    fn build_block() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let transfer = |amount| {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount,
+            })
+        };
+
+        let block = types::BlockBuilder::new(3)
+            .parent_hash(42)
+            .push(alice.clone(), transfer(10))
+            .push(alice.clone(), transfer(20))
+            .build();
+        assert_eq!(block.header.block_number, 3);
+        assert_eq!(block.header.parent_hash, 42);
+        assert_eq!(block.extrinsics.len(), 2);
+
+        // The extrinsics are signed with consecutive nonces.
+        let nonces: Vec<_> = block.extrinsics.iter().map(|e| e.nonce).collect();
+        assert_eq!(nonces, vec![0, 1]);
+        let payload = support::signing_payload(&block.extrinsics[1].call, &1, &None::<u128>);
+        assert!(support::Verify::verify(
+            &block.extrinsics[1].signature,
+            &payload,
+            &alice
+        ));
+    }
+
+    #[test]
+    fn header_hash_is_deterministic() {
+        let header = |block_number, parent_hash| types::Header {
+            block_number,
+            parent_hash,
+        };
+        assert_eq!(
+            header(1, 0).hash::<types::Hashing>(),
+            header(1, 0).hash::<types::Hashing>()
+        );
+        assert_ne!(
+            header(1, 0).hash::<types::Hashing>(),
+            header(2, 0).hash::<types::Hashing>()
+        );
+        assert_ne!(
+            header(1, 0).hash::<types::Hashing>(),
+            header(1, 1).hash::<types::Hashing>()
+        );
+    }
+
+    #[test]
+    fn block_hash_is_stored() {
+        let mut runtime = Runtime::new();
+        let header = support::Header {
+            block_number: 1,
+            parent_hash: 0,
+        };
+        let expected_hash = header.hash::<types::Hashing>();
+
+        let block = types::Block {
+            header,
+            extrinsics: vec![],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.system.block_hash(1), Some(expected_hash));
+        assert_eq!(runtime.system.block_hash(2), None);
+    }
+
+    #[test]
+    fn parent_hash_links_blocks() {
+        let mut runtime = Runtime::new();
+
+        // The first block has the default hash as parent.
+        let block_1 = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![],
+        };
+        let block_1_hash = block_1.header.hash::<types::Hashing>();
+        assert_eq!(runtime.execute_block(block_1), Ok(()));
+
+        // A block which does not link to the previous block is rejected.
+        let broken_block = types::Block {
+            header: support::Header {
+                block_number: 2,
+                parent_hash: block_1_hash + 1,
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(
+            runtime.execute_block(broken_block),
+            Err(BlockError::InvalidBlock(DispatchError::Other(
+                "parent hash does not match the previous block"
+            )))
+        );
+    }
+
+    #[test]
+    fn correct_parent_hash_is_accepted() {
+        let mut runtime = Runtime::new();
+        let block_1 = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![],
+        };
+        let block_2 = types::Block {
+            header: support::Header {
+                block_number: 2,
+                parent_hash: block_1.header.hash::<types::Hashing>(),
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(runtime.execute_block(block_1), Ok(()));
+        assert_eq!(runtime.execute_block(block_2), Ok(()));
+    }
+
+    #[test]
+    fn block_numbers_must_be_sequential() {
+        let mut runtime = Runtime::new();
+        let block_1 = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(runtime.execute_block(block_1), Ok(()));
+
+        let block_3 = types::Block {
+            header: support::Header {
+                block_number: 3,
+                parent_hash: runtime.system.block_hash(1).unwrap(),
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(
+            runtime.execute_block(block_3),
+            Err(BlockError::InvalidBlock(DispatchError::Other(
+                "block number mismatch"
+            )))
+        );
+        assert_eq!(runtime.system.block_number(), 1);
+
+        let block_2 = types::Block {
+            header: support::Header {
+                block_number: 2,
+                parent_hash: runtime.system.block_hash(1).unwrap(),
+            },
+            extrinsics: vec![],
+        };
+        assert_eq!(runtime.execute_block(block_2), Ok(()));
+        assert_eq!(runtime.system.block_number(), 2);
+
+        // No block can follow the last representable block number.
+        runtime.system.set_block_number(types::BlockNumber::MAX);
+        let block = types::Block {
+            header: support::Header {
+                block_number: 0,
+                parent_hash: 0,
+            },
+            extrinsics: vec![],
+        };
+        let overflow = BlockError::InvalidBlock(DispatchError::Other("block number overflow"));
+        assert_eq!(runtime.execute_block(block), Err(overflow.clone()));
+        assert_eq!(runtime.author_block().err(), Some(overflow));
+        assert_eq!(runtime.system.block_number(), types::BlockNumber::MAX);
+    }
+
+    #[test]
+    fn block_weight_is_limited() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        // Each transfer has a weight of 10, so 11 transfers exceed the maximum block weight.
+        let transfer = |nonce| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 1,
+                }),
+            )
+        };
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: (0..11).map(transfer).collect(),
+        };
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::InvalidBlock(DispatchError::Other(
+                "block weight exceeded"
+            )))
+        );
+        assert_eq!(runtime.system.block_number(), 0);
+        assert_eq!(runtime.balances.balance(&bob), 0);
+
+        // 10 transfers fit exactly in a block.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: (0..10).map(transfer).collect(),
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 10);
+        assert_eq!(runtime.system.block_weight(), support::Weight(100));
+    }
+
+    #[test]
+    fn dispatch_reports_actual_weight() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        let transfer = || {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount: 10,
+            })
+        };
+        let declared_weight = support::GetWeight::weight(&transfer());
+        assert_eq!(
+            runtime.dry_run(alice.clone(), transfer()),
+            Ok(support::PostDispatchInfo {
+                actual_weight: declared_weight
+            })
+        );
+
+        // A batch consumes the weight of all of its calls.
+        let batch = RuntimeCall::batch(vec![transfer(), transfer()]);
+        assert_eq!(
+            runtime.dry_run(alice.clone(), batch),
+            Ok(support::PostDispatchInfo {
+                actual_weight: support::Weight(20)
+            })
+        );
+    }
+
+    /// The `Debug` representation of each event, which can be compared exactly, in order.
+    fn debug_events(events: &[RuntimeEvent]) -> Vec<String> {
+        events.iter().map(|event| format!("{:?}", event)).collect()
+    }
+
+    #[test]
+    fn block_report_of_mixed_outcomes() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), (bob.clone(), 10)],
+            },
+            ..Default::default()
+        });
+
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
                     to: bob.clone(),
                     amount: 30,
                 }),
+            )
+            .push(
+                bob.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: alice.clone(),
+                    amount: 1000,
+                }),
+            )
+            .push(
+                alice.clone(),
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: "document".to_string(),
+                }),
+            )
+            .build();
+        let report = runtime
+            .execute_block_with_report(block)
+            .expect("the block is valid");
+
+        // The failing extrinsic did not stop the rest of the block.
+        assert_eq!(report.success_count, 2);
+        assert_eq!(report.failures, vec![(1, DispatchError::InsufficientFunds)]);
+        assert_eq!(report.total_weight, support::Weight(20));
+        assert_eq!(
+            debug_events(&report.events),
+            vec![
+                r#"balances(Endowed { account: "treasury" })"#,
+                r#"balances(Transferred { from: "alice", to: "bob", amount: 30 })"#,
+                r#"system(NewAccount { who: "treasury" })"#,
+                r#"proof_of_existence(ClaimCreated { owner: "alice", claim: "document" })"#,
+            ]
+        );
+        assert_eq!(runtime.block_number(), 1);
+        assert_eq!(runtime.claim_owner(&"document".to_string()), Some(&alice));
+
+        // Invalid blocks are still rejected as a whole.
+        let block = types::BlockBuilder::new(5).build();
+        assert!(matches!(
+            runtime.execute_block_with_report(block),
+            Err(BlockError::InvalidBlock(_))
+        ));
+    }
+
+    #[test]
+    fn events_of_a_partially_successful_batch() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
             },
-            support::Extrinsic {
+            ..Default::default()
+        });
+        let transfer = |amount| {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount,
+            })
+        };
+
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::batch(vec![transfer(10), transfer(1000)]),
+            )
+            .build();
+        let report = runtime
+            .execute_block_with_report(block)
+            .expect("the block is valid");
+
+        // The first transfer of the batch is kept, so its events are collected, and the runtime
+        // reacted to them, even though the extrinsic failed.
+        assert_eq!(report.success_count, 0);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(runtime.balances.balance(&bob), 10);
+        assert_eq!(runtime.system.providers(&bob), 1);
+        assert_eq!(
+            debug_events(&report.events),
+            vec![
+                r#"balances(Endowed { account: "treasury" })"#,
+                r#"balances(Endowed { account: "bob" })"#,
+                r#"balances(Transferred { from: "alice", to: "bob", amount: 10 })"#,
+                r#"system(NewAccount { who: "treasury" })"#,
+                r#"system(NewAccount { who: "bob" })"#,
+            ]
+        );
+        assert!(runtime.balances.events().is_empty());
+    }
+
+    #[test]
+    fn events_after_a_failing_fee_payment() {
+        let alice = "alice".to_string();
+        let carol = "carol".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), ("treasury".to_string(), 1)],
+            },
+            ..Default::default()
+        });
+
+        // Carol cannot pay the fee of the extrinsic, which leaves no events behind to be credited
+        // to the next extrinsic.
+        let block = types::BlockBuilder::new(1)
+            .push(
+                carol.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: alice.clone(),
+                    amount: 10,
+                }),
+            )
+            .push(
+                alice.clone(),
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: "document".to_string(),
+                }),
+            )
+            .build();
+        let report = runtime
+            .execute_block_with_report(block)
+            .expect("the block is valid");
+
+        assert_eq!(report.failures, vec![(0, DispatchError::InsufficientFunds)]);
+        assert_eq!(
+            debug_events(&report.events),
+            vec![r#"proof_of_existence(ClaimCreated { owner: "alice", claim: "document" })"#]
+        );
+        assert!(runtime.balances.events().is_empty());
+        assert!(runtime.proof_of_existence.events().is_empty());
+    }
+
+    #[test]
+    fn author_block_with_a_rejected_extrinsic() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let transfer = || {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount: 30,
+            })
+        };
+
+        let mut pending = runtime.author_block().unwrap();
+        assert_eq!(
+            pending.push_extrinsic(support::Extrinsic::new_signed(alice.clone(), 0, transfer())),
+            Ok(())
+        );
+        // Replaying the same nonce is rejected, but authoring continues.
+        assert_eq!(
+            pending.push_extrinsic(support::Extrinsic::new_signed(alice.clone(), 0, transfer())),
+            Err(DispatchError::InvalidNonce)
+        );
+        assert_eq!(pending.rejected(), &[(1, DispatchError::InvalidNonce)]);
+        let report = pending.seal();
+
+        // Only the valid extrinsic was sealed in the block.
+        assert_eq!(report.success_count, 1);
+        assert_eq!(report.failures, vec![(1, DispatchError::InvalidNonce)]);
+        assert_eq!(runtime.block_number(), 1);
+        assert_eq!(runtime.balances.balance(&alice), 69);
+        assert_eq!(runtime.balances.balance(&bob), 30);
+        assert_eq!(runtime.system.nonce(&alice), 1);
+
+        // The sealed block is part of the chain, so the next block builds on top of it.
+        let parent_hash = runtime.system.block_hash(1).expect("block 1 was sealed");
+        let block = types::BlockBuilder::new(2)
+            .parent_hash(parent_hash)
+            .nonce(alice.clone(), 1)
+            .push(alice.clone(), transfer())
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 60);
+    }
+
+    #[test]
+    fn root_calls_require_root_origin() {
+        let alice = "alice".to_string();
+        let root = "root".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), (root.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let set_balance = || {
+            RuntimeCall::balances(balances::Call::set_balance {
+                who: alice.clone(),
+                amount: 500,
+            })
+        };
+
+        // Not even the root account can make root calls with a signed origin.
+        assert_eq!(
+            runtime.dispatch(support::Origin::Signed(root.clone()), set_balance()),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(
+            runtime.dispatch(support::Origin::None, set_balance()),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(runtime.balances.balance(&alice), 100);
+
+        assert!(runtime
+            .dispatch(support::Origin::Root, set_balance())
+            .is_ok());
+        assert_eq!(runtime.balances.balance(&alice), 500);
+
+        // Inside a block, the root account makes root calls by wrapping them in a sudo, which
+        // other accounts cannot use.
+        let sudo = || {
+            RuntimeCall::sudo(Box::new(RuntimeCall::balances(
+                balances::Call::set_balance {
+                    who: alice.clone(),
+                    amount: 700,
+                },
+            )))
+        };
+        let block = types::BlockBuilder::new(1)
+            .push(alice.clone(), sudo())
+            .build();
+        assert_eq!(
+            runtime.execute_block_atomic(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
                 caller: alice.clone(),
-                call: RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
-                    claim: "blablub",
+                pallet: Some("utility"),
+                error: DispatchError::BadOrigin,
+            })
+        );
+        assert_eq!(runtime.balances.balance(&alice), 500);
+        let block = types::BlockBuilder::new(1)
+            .push(root.clone(), sudo())
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&alice), 700);
+
+        // Signed calls reject the root origin, since it has no account to act on behalf of.
+        let transfer = RuntimeCall::balances(balances::Call::transfer {
+            to: root.clone(),
+            amount: 10,
+        });
+        assert_eq!(
+            runtime.dispatch(support::Origin::Root, transfer),
+            Err(DispatchError::BadOrigin)
+        );
+    }
+
+    #[test]
+    fn fee_is_charged_even_if_call_fails() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 5)],
+            },
+            ..Default::default()
+        });
+
+        // `alice` can pay the fee, but not the transfer.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 5,
                 }),
+            )],
+        };
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: Some("balances"),
+                error: DispatchError::InsufficientFunds,
+            })
+        );
+        assert_eq!(runtime.balances.balance(&alice), 4);
+        assert_eq!(runtime.balances.balance(&bob), 0);
+        assert_eq!(runtime.balances.balance(&"treasury".to_string()), 1);
+        assert_eq!(runtime.balances.total_issuance(), 5);
+    }
+
+    #[test]
+    fn fees_are_paid_to_the_treasury() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let treasury = "treasury".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
             },
-        ],
-    };
+            ..Default::default()
+        });
 
-    let block_2 = types::Block {
-        header: support::Header { block_number: 2 },
-        extrinsics: vec![support::Extrinsic {
-            caller: alice.clone(),
-            call: RuntimeCall::balances(balances::Call::transfer {
-                to: charlie.clone(),
-                amount: 20,
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 30,
+                }),
+            )],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+
+        // The fee of the transfer was credited to the treasury, instead of being burned.
+        assert_eq!(runtime.balances.balance(&alice), 69);
+        assert_eq!(runtime.balances.balance(&bob), 30);
+        assert_eq!(runtime.balances.balance(&treasury), 1);
+        assert_eq!(runtime.balances.total_issuance(), 100);
+    }
+
+    #[test]
+    fn call_is_not_dispatched_without_fee() {
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new();
+
+        // `alice` could create the claim, but cannot pay the fee.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: "document".to_string(),
+                }),
+            )],
+        };
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: None,
+                error: DispatchError::InsufficientFunds,
+            })
+        );
+        assert_eq!(
+            runtime
+                .proof_of_existence
+                .get_claim(&"document".to_string()),
+            None
+        );
+    }
+
+    #[test]
+    fn tampered_extrinsic_is_rejected() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let charlie = "charlie".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        // The call is replaced after `alice` signed the extrinsic.
+        let mut extrinsic = support::Extrinsic::new_signed(
+            alice.clone(),
+            0,
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount: 10,
             }),
-        }],
-    };
+        );
+        extrinsic.call = RuntimeCall::balances(balances::Call::transfer {
+            to: charlie.clone(),
+            amount: 90,
+        });
 
-    runtime.execute_block(block_1).expect("invalid block");
-    runtime.execute_block(block_2).expect("invalid block");
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![extrinsic],
+        };
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: None,
+                error: DispatchError::InvalidSignature,
+            })
+        );
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert_eq!(runtime.balances.balance(&charlie), 0);
+        assert_eq!(runtime.system.nonce(&alice), 0);
+    }
 
-    println!("{:#?}", runtime);
+    #[test]
+    fn atomic_block_reverts_on_failure() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        let transfer = |nonce, amount| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount,
+                }),
+            )
+        };
+
+        // The second transfer overdraws the account of `alice`.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![transfer(0, 60), transfer(1, 60)],
+        };
+        assert_eq!(
+            runtime.execute_block_atomic(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 1,
+                caller: alice.clone(),
+                pallet: Some("balances"),
+                error: DispatchError::InsufficientFunds,
+            })
+        );
+
+        // The effects of the first transfer were rolled back as well.
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert_eq!(runtime.balances.balance(&bob), 0);
+        assert_eq!(runtime.system.nonce(&alice), 0);
+        assert_eq!(runtime.system.block_number(), 0);
+
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![transfer(0, 60)],
+        };
+        assert_eq!(runtime.execute_block_atomic(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&alice), 39);
+        assert_eq!(runtime.balances.balance(&bob), 60);
+    }
+
+    #[test]
+    fn dry_run_does_not_change_state() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        let transfer = |amount| {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount,
+            })
+        };
+        assert_eq!(
+            runtime.dry_run(alice.clone(), transfer(150)),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert!(runtime.dry_run(alice.clone(), transfer(50)).is_ok());
+
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert_eq!(runtime.balances.balance(&bob), 0);
+        assert!(runtime.system.events().is_empty());
+    }
+
+    #[test]
+    fn call_names() {
+        let call = RuntimeCall::balances(balances::Call::transfer {
+            to: "bob".to_string(),
+            amount: 10,
+        });
+        assert_eq!(call.name(), "balances::transfer");
+
+        let names = RuntimeCall::call_names();
+        assert!(names.contains(&"balances::transfer".to_string()));
+        assert!(names.contains(&"proof_of_existence::create_claim".to_string()));
+    }
+
+    #[test]
+    fn display_calls() {
+        let transfer = RuntimeCall::balances(balances::Call::transfer {
+            to: "bob".to_string(),
+            amount: 30,
+        });
+        assert_eq!(
+            transfer.to_string(),
+            r#"balances::transfer { to: "bob", amount: 30 }"#
+        );
+
+        let batch = RuntimeCall::batch(vec![
+            transfer,
+            RuntimeCall::timestamp(timestamp::Call::set { now: 1000 }),
+        ]);
+        assert_eq!(
+            batch.to_string(),
+            r#"utility::batch [balances::transfer { to: "bob", amount: 30 }, timestamp::set { now: 1000 }]"#
+        );
+    }
+
+    // Run with `RUST_LOG=debug cargo test --features logging -- --nocapture` to see the logs.
+    #[cfg(feature = "logging")]
+    #[test]
+    fn log_dispatched_calls() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+
+        // Logs `dispatch balances::transfer by alice -> Ok`.
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: "bob".to_string(),
+                    amount: 10,
+                }),
+            )],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+    }
+
+    #[test]
+    fn kill_account() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let root = "root".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), (bob.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        assert_eq!(runtime.system.inc_nonce(&alice), Ok(()));
+        assert_eq!(
+            runtime.balances.approve(alice.clone(), bob.clone(), 10),
+            Ok(())
+        );
+        assert_eq!(
+            runtime.balances.approve(bob.clone(), alice.clone(), 20),
+            Ok(())
+        );
+        assert_eq!(
+            runtime
+                .balances
+                .mint_asset(root.clone(), 1, alice.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(
+            runtime
+                .balances
+                .mint_asset(root.clone(), 1, bob.clone(), 50),
+            Ok(())
+        );
+        assert_eq!(runtime.balances.freeze(root, alice.clone()), Ok(()));
+
+        assert_eq!(runtime.kill_account(&alice), Ok(()));
+        assert_eq!(runtime.balances.balance(&alice), 0);
+        assert_eq!(runtime.balances.total_issuance(), 100);
+        assert_eq!(runtime.system.nonce(&alice), 0);
+        assert_eq!(runtime.balances.allowance(&alice, &bob), 0);
+        assert_eq!(runtime.balances.allowance(&bob, &alice), 0);
+        assert_eq!(runtime.balances.asset_balance(&1, &alice), 0);
+        assert!(!runtime.balances.is_frozen(&alice));
+
+        // The state of other accounts is untouched.
+        assert_eq!(runtime.balances.balance(&bob), 100);
+        assert_eq!(runtime.balances.asset_balance(&1, &bob), 50);
+    }
+
+    #[test]
+    fn funded_accounts_exist() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        assert!(runtime.system.account_exists(&alice));
+        assert!(!runtime.system.account_exists(&bob));
+
+        // A fresh account gains a provider when it is funded.
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 30,
+                }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.system.providers(&bob), 1);
+        assert!(runtime.system.events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::system(system::Event::NewAccount { who }) if who == &bob
+        )));
+
+        // And loses it when its balance is reaped. Bob pays a fee of 1 for the transfer.
+        let block = types::BlockBuilder::new(2)
+            .parent_hash(runtime.system.block_hash(1).unwrap())
+            .push(
+                bob.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: alice.clone(),
+                    amount: 29,
+                }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 0);
+        assert_eq!(runtime.system.providers(&bob), 0);
+        assert!(!runtime.system.account_exists(&bob));
+        assert!(runtime.system.events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::system(system::Event::KilledAccount { who }) if who == &bob
+        )));
+        assert!(runtime.system.account_exists(&alice));
+    }
+
+    #[test]
+    fn state_root() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let genesis = || RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            proof_of_existence: proof_of_existence::GenesisConfig {
+                claims: vec![("document".to_string(), bob.clone())],
+            },
+            ..Default::default()
+        };
+        let block = || {
+            types::BlockBuilder::new(1)
+                .push(
+                    alice.clone(),
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 30,
+                    }),
+                )
+                .build()
+        };
+
+        // Identical state gives an identical root.
+        let mut runtime = Runtime::new_with_genesis(genesis());
+        let mut other = Runtime::new_with_genesis(genesis());
+        let genesis_root = runtime.state_root();
+        assert_eq!(genesis_root, other.state_root());
+        assert_eq!(genesis_root, runtime.state_root());
+
+        // Executing a transfer changes the root, in the same way for both runtimes.
+        assert_eq!(runtime.execute_block(block()), Ok(()));
+        assert_ne!(runtime.state_root(), genesis_root);
+        assert_eq!(other.execute_block(block()), Ok(()));
+        assert_eq!(runtime.state_root(), other.state_root());
+    }
+
+    #[test]
+    fn pallet_indices() {
+        assert_eq!(Runtime::pallet_index("system"), Some(0));
+        assert_eq!(Runtime::pallet_index("balances"), Some(1));
+        assert_eq!(Runtime::pallet_index("proof_of_existence"), Some(2));
+        assert_eq!(Runtime::pallet_index("timestamp"), Some(3));
+        assert_eq!(Runtime::pallet_index("unknown"), None);
+
+        assert_eq!(Runtime::pallet_name(1), Some("balances"));
+        assert_eq!(Runtime::pallet_index("utility"), Some(4));
+        assert_eq!(Runtime::pallet_name(5), None);
+
+        let call = RuntimeCall::timestamp(timestamp::Call::set { now: 1 });
+        assert_eq!(Runtime::pallet_name(call.pallet_index()), Some("timestamp"));
+    }
+
+    #[test]
+    fn batch_calls() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let batch = RuntimeCall::batch(vec![
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount: 30,
+            }),
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                claim: "document".to_string(),
+            }),
+        ]);
+        assert_eq!(batch.name(), "utility::batch");
+        assert_eq!(batch.pallet_name(), "utility");
+        assert_eq!(support::GetWeight::weight(&batch), support::Weight(20));
+
+        let block = types::BlockBuilder::new(1)
+            .push(alice.clone(), batch)
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+
+        // The fee of the batch is based on the total weight of its calls.
+        assert_eq!(runtime.balances.balance(&alice), 68);
+        assert_eq!(runtime.balances.balance(&bob), 30);
+        assert_eq!(runtime.claim_owner(&"document".to_string()), Some(&alice));
+        assert_eq!(runtime.nonce_of(&alice), 1);
+
+        // A failing call interrupts the batch, but the calls before it are not reverted.
+        let block = types::BlockBuilder::new(2)
+            .parent_hash(runtime.system.block_hash(1).unwrap())
+            .nonce(alice.clone(), 1)
+            .push(
+                alice.clone(),
+                RuntimeCall::batch(vec![
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 10,
+                    }),
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 1000,
+                    }),
+                ]),
+            )
+            .build();
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: Some("utility"),
+                error: DispatchError::BatchInterrupted {
+                    index: 1,
+                    error: Box::new(DispatchError::InsufficientFunds),
+                },
+            })
+        );
+        assert_eq!(runtime.balances.balance(&alice), 56);
+        assert_eq!(runtime.balances.balance(&bob), 40);
+    }
+
+    #[test]
+    fn batch_all_calls_are_atomic() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::batch_all(vec![
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 30,
+                    }),
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: bob.clone(),
+                        amount: 1000,
+                    }),
+                ]),
+            )
+            .build();
+        assert_eq!(
+            runtime.execute_block(block),
+            Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: Some("utility"),
+                error: DispatchError::BatchInterrupted {
+                    index: 1,
+                    error: Box::new(DispatchError::InsufficientFunds),
+                },
+            })
+        );
+
+        // The first transfer was reverted, but the fee and nonce of the extrinsic were not.
+        assert_eq!(runtime.balances.balance(&alice), 98);
+        assert_eq!(runtime.balances.balance(&bob), 0);
+        assert_eq!(runtime.nonce_of(&alice), 1);
+    }
+
+    #[test]
+    fn claim_dynamic_content() {
+        let alice = "alice".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let mut builder = types::BlockBuilder::new(1);
+        for i in 0..3 {
+            builder = builder.push(
+                alice.clone(),
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: format!("document-{}", i),
+                }),
+            );
+        }
+        assert_eq!(runtime.execute_block(builder.build()), Ok(()));
+
+        for i in 0..3 {
+            assert_eq!(
+                runtime.claim_owner(&format!("document-{}", i)),
+                Some(&alice)
+            );
+        }
+        assert_eq!(runtime.claim_owner(&format!("document-{}", 3)), None);
+    }
+
+    #[test]
+    fn query_runtime_state() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            proof_of_existence: proof_of_existence::GenesisConfig {
+                claims: vec![("document".to_string(), bob.clone())],
+            },
+            ..Default::default()
+        });
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::timestamp(timestamp::Call::set { now: 1000 }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+
+        assert_eq!(runtime.balance_of(&alice), 100);
+        assert_eq!(runtime.balance_of(&bob), 0);
+        assert_eq!(runtime.nonce_of(&alice), 1);
+        assert_eq!(runtime.nonce_of(&bob), 0);
+        assert_eq!(runtime.claim_owner(&"document".to_string()), Some(&bob));
+        assert_eq!(runtime.claim_owner(&"unknown".to_string()), None);
+        assert_eq!(runtime.block_number(), 1);
+        assert_eq!(runtime.current_time(), 1000);
+    }
+
+    #[test]
+    fn apply_single_extrinsics() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let transfer = |nonce| {
+            support::Extrinsic::new_signed(
+                alice.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 10,
+                }),
+            )
+        };
+
+        assert_eq!(runtime.apply_extrinsic(transfer(0)), Ok(()));
+        assert_eq!(runtime.nonce_of(&alice), 1);
+        assert_eq!(runtime.apply_extrinsic(transfer(1)), Ok(()));
+        assert_eq!(runtime.nonce_of(&alice), 2);
+
+        // Replaying an extrinsic is rejected.
+        assert_eq!(
+            runtime.apply_extrinsic(transfer(1)),
+            Err(DispatchError::InvalidNonce)
+        );
+        assert_eq!(runtime.nonce_of(&alice), 2);
+
+        // Each transfer paid a fee of 1, and the block number did not advance.
+        assert_eq!(runtime.balance_of(&alice), 78);
+        assert_eq!(runtime.balance_of(&bob), 20);
+        assert_eq!(runtime.block_number(), 0);
+        assert_eq!(runtime.system.extrinsic_count(), 2);
+    }
+
+    #[test]
+    fn failing_fee_leaves_no_trace() {
+        let carol = "carol".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![("alice".to_string(), 100)],
+            },
+            ..Default::default()
+        });
+        let account_count = runtime.system.account_count();
+
+        // Carol has no funds to pay the fee, so the nonce of carol is not used up.
+        let extrinsic = support::Extrinsic::new_signed(
+            carol.clone(),
+            0,
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                claim: "document".to_string(),
+            }),
+        );
+        assert_eq!(
+            runtime.apply_extrinsic(extrinsic),
+            Err(DispatchError::InsufficientFunds)
+        );
+        assert_eq!(runtime.nonce_of(&carol), 0);
+        assert_eq!(runtime.system.account_count(), account_count);
+        assert_eq!(runtime.system.extrinsic_count(), 0);
+    }
+
+    #[test]
+    fn validate_transactions() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let transfer = |caller: &types::AccountId, nonce| {
+            support::Extrinsic::new_signed(
+                caller.clone(),
+                nonce,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: "charlie".to_string(),
+                    amount: 10,
+                }),
+            )
+        };
+        assert_eq!(runtime.apply_extrinsic(transfer(&alice, 0)), Ok(()));
+
+        let valid = support::ValidTransaction {
+            priority: 1,
+            ready: true,
+        };
+        assert_eq!(
+            runtime.validate_transaction(&transfer(&alice, 1)),
+            Ok(valid.clone())
+        );
+        // A future nonce is valid, but must wait for the transactions before it.
+        assert_eq!(
+            runtime.validate_transaction(&transfer(&alice, 5)),
+            Ok(support::ValidTransaction {
+                ready: false,
+                ..valid
+            })
+        );
+        // A stale nonce can never be applied.
+        assert_eq!(
+            runtime.validate_transaction(&transfer(&alice, 0)),
+            Err(DispatchError::InvalidNonce)
+        );
+        // Bob cannot afford the fee.
+        assert_eq!(
+            runtime.validate_transaction(&transfer(&bob, 0)),
+            Err(DispatchError::InsufficientFunds)
+        );
+        let mut forged = transfer(&alice, 1);
+        forged.caller = bob.clone();
+        assert_eq!(
+            runtime.validate_transaction(&forged),
+            Err(DispatchError::InvalidSignature)
+        );
+        // Calls which the runtime would reject before dispatching them are not valid either.
+        let claim = support::Extrinsic::new_signed(
+            alice.clone(),
+            1,
+            RuntimeCall::proof_of_existence(proof_of_existence::Call::transfer_claim {
+                claim: "document".to_string(),
+                new_owner: bob.clone(),
+            }),
+        );
+        assert_eq!(
+            runtime.validate_transaction(&claim),
+            Err(DispatchError::NoProviders)
+        );
+
+        // Validation does not apply anything.
+        assert_eq!(runtime.nonce_of(&alice), 1);
+        assert_eq!(runtime.balance_of(&alice), 89);
+    }
+
+    #[test]
+    fn tipped_extrinsics_are_prioritized() {
+        let alice = "alice".to_string();
+        let treasury = "treasury".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let transfer = |tip| {
+            support::Extrinsic::new_signed_with_tip(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: "bob".to_string(),
+                    amount: 10,
+                }),
+                tip,
+            )
+        };
+
+        // The priority is the base fee of 1 plus the tip.
+        let priority = |tip| {
+            runtime
+                .validate_transaction(&transfer(tip))
+                .map(|valid| valid.priority)
+        };
+        assert_eq!(priority(None), Ok(1));
+        assert_eq!(priority(Some(5)), Ok(6));
+        assert_eq!(priority(Some(100)), Err(DispatchError::InsufficientFunds));
+
+        // The tip is charged together with the base fee.
+        assert_eq!(runtime.apply_extrinsic(transfer(Some(5))), Ok(()));
+        assert_eq!(runtime.balance_of(&alice), 84);
+        assert_eq!(runtime.balance_of(&treasury), 6);
+
+        // The tip is signed, so it cannot be changed afterwards.
+        let mut extrinsic = transfer(Some(5));
+        extrinsic.nonce = 1;
+        extrinsic.tip = None;
+        assert_eq!(
+            runtime.apply_extrinsic(extrinsic),
+            Err(DispatchError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let snapshot = runtime.snapshot();
+        let root = runtime.state_root();
+
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 30,
+                }),
+            )
+            .push(
+                alice.clone(),
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                    claim: "document".to_string(),
+                }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balance_of(&bob), 30);
+
+        runtime.restore(snapshot);
+        assert_eq!(runtime.state_root(), root);
+        assert_eq!(runtime.balance_of(&alice), 100);
+        assert_eq!(runtime.balance_of(&bob), 0);
+        assert_eq!(runtime.nonce_of(&alice), 0);
+        assert_eq!(runtime.claim_owner(&"document".to_string()), None);
+        assert_eq!(runtime.block_number(), 0);
+    }
+
+    #[test]
+    fn block_number_after_two_blocks() {
+        let mut runtime = Runtime::new();
+        let block_1 = types::BlockBuilder::new(1).build();
+        let block_2 = types::BlockBuilder::new(2)
+            .parent_hash(block_1.header.hash::<types::Hashing>())
+            .build();
+
+        assert_eq!(runtime.execute_block(block_1), Ok(()));
+        assert_eq!(runtime.execute_block(block_2), Ok(()));
+        assert_eq!(runtime.block_number(), 2);
+    }
+
+    /// Build a chain of blocks with the given block numbers, each linked to the previous one and
+    /// containing a transfer of 10 from alice to bob.
+    fn transfer_chain(block_numbers: &[u32]) -> Vec<types::Block> {
+        let mut parent_hash = 0;
+        let mut blocks = Vec::new();
+        for (nonce, block_number) in block_numbers.iter().enumerate() {
+            let block = types::BlockBuilder::new(*block_number)
+                .parent_hash(parent_hash)
+                .nonce("alice".to_string(), nonce as u32)
+                .push(
+                    "alice".to_string(),
+                    RuntimeCall::balances(balances::Call::transfer {
+                        to: "bob".to_string(),
+                        amount: 10,
+                    }),
+                )
+                .build();
+            parent_hash = block.header.hash::<types::Hashing>();
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    #[test]
+    fn replay_a_chain_of_blocks() {
+        let genesis = || RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![("alice".to_string(), 100)],
+            },
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new_with_genesis(genesis());
+        assert_eq!(runtime.execute_blocks(transfer_chain(&[1, 2, 3])), Ok(()));
+        assert_eq!(runtime.block_number(), 3);
+        assert_eq!(runtime.balances.balance(&"bob".to_string()), 30);
+
+        // Replaying the same chain on the same genesis leads to the same state.
+        let mut replayed = Runtime::new_with_genesis(genesis());
+        for block in transfer_chain(&[1, 2, 3]) {
+            assert_eq!(replayed.execute_block(block), Ok(()));
+        }
+        assert_eq!(replayed.state_root(), runtime.state_root());
+    }
+
+    #[test]
+    fn replay_stops_at_a_gap() {
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![("alice".to_string(), 100)],
+            },
+            ..Default::default()
+        });
+        let result = runtime.execute_blocks(transfer_chain(&[1, 2, 4, 5]));
+        assert!(matches!(result, Err((4, BlockError::InvalidBlock(_)))));
+
+        // The blocks before the gap are kept.
+        assert_eq!(runtime.block_number(), 2);
+        assert_eq!(runtime.balances.balance(&"bob".to_string()), 20);
+    }
+
+    #[test]
+    fn run_blocks_with_a_bad_block() {
+        let alice = "alice".to_string();
+        let genesis = || RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 25)],
+            },
+            ..Default::default()
+        };
+        // Alice can only afford two of the three transfers, so the third block fails.
+        let blocks = || {
+            let mut blocks = transfer_chain(&[1, 2, 3]);
+            blocks.push(
+                types::BlockBuilder::new(4)
+                    .parent_hash(blocks[2].header.hash::<types::Hashing>())
+                    .build(),
+            );
+            blocks
+        };
+        let bad_block = BlockOutcome {
+            block_number: 3,
+            result: Err(BlockError::ExtrinsicFailed {
+                index: 0,
+                caller: alice.clone(),
+                pallet: Some("balances"),
+                error: DispatchError::InsufficientFunds,
+            }),
+        };
+
+        // Block 3 is rolled back, so the runtime stays at block 2.
+        let mut runtime = Runtime::new_with_genesis(genesis());
+        let outcomes = run_blocks(&mut runtime, blocks(), OnBlockError::Stop);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[1].result, Ok(()));
+        assert_eq!(outcomes[2], bad_block);
+        assert_eq!(runtime.block_number(), 2);
+        assert_eq!(runtime.balance_of(&alice), 3);
+
+        // The empty block 4 was built on top of block 3, which was rolled back.
+        let mut runtime = Runtime::new_with_genesis(genesis());
+        let outcomes = run_blocks(&mut runtime, blocks(), OnBlockError::Continue);
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(outcomes[2], bad_block);
+        assert!(matches!(
+            outcomes[3].result,
+            Err(BlockError::InvalidBlock(_))
+        ));
+        assert_eq!(runtime.block_number(), 2);
+    }
+
+    #[test]
+    fn run_blocks_continues_after_a_rolled_back_block() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let block_1 = transfer_chain(&[1]).remove(0);
+        let block_1_hash = block_1.header.hash::<types::Hashing>();
+        let transfer = |amount| {
+            RuntimeCall::balances(balances::Call::transfer {
+                to: bob.clone(),
+                amount,
+            })
+        };
+        // The second block pays a transfer before failing on a transfer alice cannot afford.
+        let bad_block = types::BlockBuilder::new(2)
+            .parent_hash(block_1_hash)
+            .nonce(alice.clone(), 1)
+            .push(alice.clone(), transfer(20))
+            .push(alice.clone(), transfer(1000))
+            .build();
+        // The third block replaces the second one, on top of block 1.
+        let replacement = types::BlockBuilder::new(2)
+            .parent_hash(block_1_hash)
+            .nonce(alice.clone(), 1)
+            .push(alice.clone(), transfer(5))
+            .build();
+
+        let outcomes = run_blocks(
+            &mut runtime,
+            vec![block_1, bad_block, replacement],
+            OnBlockError::Continue,
+        );
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].result, Ok(()));
+        assert!(matches!(
+            outcomes[1].result,
+            Err(BlockError::ExtrinsicFailed { index: 1, .. })
+        ));
+        assert_eq!(outcomes[2].result, Ok(()));
+        assert_eq!(runtime.block_number(), 2);
+        // Only the transfers of block 1 and its replacement were applied, with their fees.
+        assert_eq!(runtime.balance_of(&bob), 15);
+        assert_eq!(runtime.nonce_of(&alice), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid block 3")]
+    fn run_blocks_can_panic() {
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![("alice".to_string(), 25)],
+            },
+            ..Default::default()
+        });
+        run_blocks(
+            &mut runtime,
+            transfer_chain(&[1, 2, 3]),
+            OnBlockError::Panic,
+        );
+    }
+
+    #[test]
+    fn start_at_block_number() {
+        let mut runtime = Runtime::new_at(100);
+        assert_eq!(runtime.block_number(), 100);
+
+        let block = types::BlockBuilder::new(1).build();
+        assert!(matches!(
+            runtime.execute_block(block),
+            Err(BlockError::InvalidBlock(_))
+        ));
+
+        let block = types::BlockBuilder::new(101).build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.block_number(), 101);
+        assert!(runtime.system.block_hash(101).is_some());
+    }
+
+    #[test]
+    fn runtime_from_genesis() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100), (bob.clone(), 50)],
+            },
+            proof_of_existence: proof_of_existence::GenesisConfig {
+                claims: vec![("document".to_string(), bob.clone())],
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert_eq!(runtime.balances.balance(&bob), 50);
+        assert_eq!(runtime.balances.total_issuance(), 150);
+        assert_eq!(
+            runtime
+                .proof_of_existence
+                .get_claim(&"document".to_string()),
+            Some(&bob)
+        );
+        assert_eq!(runtime.system.block_number(), 0);
+        assert!(runtime.proof_of_existence.events().is_empty());
+    }
+
+    #[test]
+    fn combined_genesis_of_all_pallets() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            system: system::GenesisConfig { block_number: 10 },
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            proof_of_existence: proof_of_existence::GenesisConfig {
+                claims: vec![("document".to_string(), alice.clone())],
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(runtime.system.block_number(), 10);
+        assert!(runtime.system.account_exists(&alice));
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert!(runtime.proof_of_existence.exists(&"document".to_string()));
+        assert!(runtime.system.events().is_empty());
+
+        // The chain continues from the genesis block number, with the seeded state.
+        let block = types::BlockBuilder::new(11)
+            .push(
+                alice.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 30,
+                }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 30);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn encode_and_decode_calls() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let call = RuntimeCall::balances(balances::Call::transfer {
+            to: bob.clone(),
+            amount: 30,
+        });
+
+        let decoded = RuntimeCall::decode(&call.encode()).unwrap();
+        assert!(matches!(
+            &decoded,
+            RuntimeCall::balances(balances::Call::transfer { to, amount: 30 }) if to == &bob
+        ));
+        assert!(RuntimeCall::decode(b"not a call").is_err());
+
+        // A whole extrinsic can be serialized, and its signature is still valid afterwards.
+        let extrinsic: types::Extrinsic = support::Extrinsic::new_signed(alice.clone(), 0, decoded);
+        let json = serde_json::to_string(&extrinsic).unwrap();
+        let extrinsic: types::Extrinsic = serde_json::from_str(&json).unwrap();
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![extrinsic],
+        };
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        assert_eq!(runtime.balances.balance(&bob), 30);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn export_and_import_state() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            proof_of_existence: proof_of_existence::GenesisConfig {
+                claims: vec![("document".to_string(), bob.clone())],
+            },
+            ..Default::default()
+        });
+        let block = types::Block {
+            header: support::Header {
+                block_number: 1,
+                parent_hash: 0,
+            },
+            extrinsics: vec![support::Extrinsic::new_signed(
+                alice.clone(),
+                0,
+                RuntimeCall::balances(balances::Call::approve {
+                    spender: bob.clone(),
+                    amount: 10,
+                }),
+            )],
+        };
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        let snapshot = runtime.export_state();
+
+        // Mutate the state after taking the snapshot.
+        assert_eq!(
+            runtime.balances.transfer(alice.clone(), bob.clone(), 40),
+            Ok(())
+        );
+        assert_ne!(runtime.export_state(), snapshot);
+
+        assert!(runtime.import_state(&snapshot).is_ok());
+        assert_eq!(runtime.export_state(), snapshot);
+        assert_eq!(runtime.balances.balance(&alice), 100);
+        assert_eq!(runtime.balances.allowance(&alice, &bob), 10);
+        assert_eq!(runtime.system.nonce(&alice), 1);
+        assert_eq!(runtime.system.block_number(), 1);
+        assert_eq!(
+            runtime
+                .proof_of_existence
+                .get_claim(&"document".to_string()),
+            Some(&bob)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn state_root_survives_export_and_import() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+            balances: balances::GenesisConfig {
+                balances: vec![(alice.clone(), 100)],
+            },
+            ..Default::default()
+        });
+        let block = types::BlockBuilder::new(1)
+            .push(
+                alice.clone(),
+                RuntimeCall::balances(balances::Call::transfer {
+                    to: bob.clone(),
+                    amount: 30,
+                }),
+            )
+            .build();
+        assert_eq!(runtime.execute_block(block), Ok(()));
+        // The block left its events and extrinsic count behind, which are not exported.
+        assert!(!runtime.system.events().is_empty());
+
+        let mut imported = Runtime::new();
+        assert!(imported.import_state(&runtime.export_state()).is_ok());
+        assert!(imported.system.events().is_empty());
+        assert_eq!(imported.state_root(), runtime.state_root());
+    }
+
+    /// A small deterministic pseudo random number generator (xorshift64), so that every fuzz run
+    /// with the same seed dispatches the same calls.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// A random number below `n`.
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.below(items.len() as u64) as usize]
+        }
+
+        /// A random amount, which is usually small, but sometimes huge to provoke overflows.
+        fn amount(&mut self) -> types::Balance {
+            match self.below(10) {
+                0 => types::Balance::MAX - self.below(3) as types::Balance,
+                _ => self.below(200) as types::Balance,
+            }
+        }
+    }
+
+    const FUZZ_ACCOUNTS: [&str; 4] = ["alice", "bob", "charlie", "treasury"];
+    const FUZZ_CLAIMS: [&str; 4] = ["a", "b", "c", "d"];
+
+    /// Generate a random call to any pallet of the runtime, including batches of random calls.
+    fn random_call(rng: &mut Rng, depth: u32) -> RuntimeCall {
+        let mut account = || rng.pick(&FUZZ_ACCOUNTS).to_string();
+        let who = account();
+        let to = account();
+        let claim = rng.pick(&FUZZ_CLAIMS).to_string();
+        let claims = (0..rng.below(3))
+            .map(|_| rng.pick(&FUZZ_CLAIMS).to_string())
+            .collect::<Vec<_>>();
+        let amount = rng.amount();
+        let asset_id = rng.below(2) as types::AssetId;
+        let choices = if depth == 0 { 29 } else { 26 };
+        match rng.below(choices) {
+            0 => RuntimeCall::balances(balances::Call::transfer { to, amount }),
+            1 => RuntimeCall::balances(balances::Call::transfer_keep_alive { to, amount }),
+            2 => RuntimeCall::balances(balances::Call::transfer_many {
+                payouts: vec![(who, amount), (to, rng.amount())],
+            }),
+            3 => RuntimeCall::balances(balances::Call::force_transfer {
+                from: who,
+                to,
+                amount,
+            }),
+            4 => RuntimeCall::balances(balances::Call::set_balance { who, amount }),
+            5 => RuntimeCall::balances(balances::Call::transfer_all { to }),
+            6 => RuntimeCall::balances(balances::Call::approve {
+                spender: to,
+                amount,
+            }),
+            7 => RuntimeCall::balances(balances::Call::transfer_from {
+                owner: who,
+                to,
+                amount,
+            }),
+            8 => RuntimeCall::balances(balances::Call::mint { to, amount }),
+            9 => RuntimeCall::balances(balances::Call::burn { amount }),
+            10 => RuntimeCall::balances(balances::Call::transfer_asset {
+                asset_id,
+                to,
+                amount,
+            }),
+            11 => RuntimeCall::balances(balances::Call::mint_asset {
+                asset_id,
+                to,
+                amount,
+            }),
+            12 => RuntimeCall::balances(balances::Call::freeze { who }),
+            13 => RuntimeCall::balances(balances::Call::thaw { who }),
+            14 => RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim { claim }),
+            15 => RuntimeCall::proof_of_existence(
+                proof_of_existence::Call::create_claim_with_metadata {
+                    claim,
+                    metadata: who,
+                },
+            ),
+            16 => RuntimeCall::proof_of_existence(proof_of_existence::Call::set_metadata {
+                claim,
+                metadata: who,
+            }),
+            17 => {
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claims { claims })
+            }
+            18 => RuntimeCall::proof_of_existence(proof_of_existence::Call::revoke_claim { claim }),
+            19 => {
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::revoke_claims { claims })
+            }
+            20 => RuntimeCall::proof_of_existence(proof_of_existence::Call::force_revoke_claim {
+                claim,
+            }),
+            21 => RuntimeCall::proof_of_existence(proof_of_existence::Call::transfer_claim {
+                claim,
+                new_owner: to,
+            }),
+            22 => RuntimeCall::proof_of_existence(proof_of_existence::Call::add_approver {
+                claim,
+                who: to,
+            }),
+            23 => RuntimeCall::timestamp(timestamp::Call::set {
+                now: rng.below(1000),
+            }),
+            24 => RuntimeCall::proof_of_existence(proof_of_existence::Call::create_claim {
+                claim: "x".repeat(2000),
+            }),
+            25 => {
+                RuntimeCall::proof_of_existence(proof_of_existence::Call::revoke_all { owner: who })
+            }
+            26 => RuntimeCall::sudo(Box::new(random_call(rng, depth + 1))),
+            27 => RuntimeCall::batch((0..3).map(|_| random_call(rng, depth + 1)).collect()),
+            _ => RuntimeCall::batch_all((0..3).map(|_| random_call(rng, depth + 1)).collect()),
+        }
+    }
+
+    /// Check the invariants which must hold across all the pallets after every call.
+    fn check_invariants(runtime: &Runtime) {
+        // The total issuance is exactly the sum of all the balances.
+        let total = runtime
+            .balances
+            .accounts()
+            .fold(0 as types::Balance, |total, (who, free)| {
+                total
+                    .checked_add(*free)
+                    .and_then(|total| total.checked_add(runtime.balances.reserved_balance(who)))
+                    .expect("the balances exceed the total issuance")
+            });
+        assert_eq!(total, runtime.balances.total_issuance());
+
+        // Every claim has exactly one owner, which counts it among its claims.
+        let claims = runtime.proof_of_existence.claims_ordered();
+        for (hash, owner) in &claims {
+            assert_eq!(
+                runtime.proof_of_existence.get_claim_by_hash(hash),
+                Some(owner)
+            );
+        }
+        let mut counted = 0;
+        for owner in FUZZ_ACCOUNTS.map(String::from) {
+            let owned = runtime.proof_of_existence.claims_by_owner(&owner).len();
+            assert_eq!(owned as u32, runtime.proof_of_existence.claims_of(&owner));
+            counted += owned;
+        }
+        assert_eq!(counted, claims.len());
+    }
+
+    #[test]
+    fn fuzz_dispatch() {
+        for seed in 1..=8 {
+            let mut rng = Rng(seed);
+            let mut runtime = Runtime::new_with_genesis(RuntimeGenesisConfig {
+                balances: balances::GenesisConfig {
+                    balances: FUZZ_ACCOUNTS
+                        .iter()
+                        .map(|who| (who.to_string(), 1000))
+                        .collect(),
+                },
+                ..Default::default()
+            });
+            for _ in 0..500 {
+                let call = random_call(&mut rng, 0);
+                let caller = rng.pick(&FUZZ_ACCOUNTS).to_string();
+                // Most calls are submitted as extrinsics, which also pay fees, but some are
+                // dispatched as root, and some blocks are executed to run the hooks.
+                match rng.below(20) {
+                    0 => {
+                        let _ = runtime.dispatch(support::Origin::Root, call);
+                    }
+                    1 => {
+                        let number = runtime.block_number() + 1;
+                        let parent_hash = runtime
+                            .system
+                            .block_hash(runtime.block_number())
+                            .unwrap_or_default();
+                        let block = types::BlockBuilder::new(number)
+                            .parent_hash(parent_hash)
+                            .build();
+                        assert_eq!(runtime.execute_block(block), Ok(()));
+                    }
+                    _ => {
+                        let nonce = runtime.system.nonce(&caller);
+                        let extrinsic = support::Extrinsic::new_signed(caller, nonce, call);
+                        let _ = runtime.apply_extrinsic(extrinsic);
+                    }
+                }
+                check_invariants(&runtime);
+            }
+        }
+    }
 }
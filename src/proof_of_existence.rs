@@ -1,62 +1,599 @@
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
 use core::fmt::Debug;
-use std::collections::BTreeMap;
 
-use crate::support::DispatchResult;
+use num::{CheckedAdd, CheckedSub, Zero};
+
+use crate::support::{DispatchError, DispatchResult, EncodeStorage, Hashing, Hooks, StorageMap};
 
 pub trait Config: crate::system::Config {
     /// The type which represents the content that can be claimed using this pallet.
-    /// Could be the content directly as bytes, or better yet the hash of that content.
-    /// We leave that decision to the runtime developer.
-    type Content: Debug + Ord;
+    /// The content is hashed before it is stored, so it can be arbitrarily large. It is only ever
+    /// cloned, never copied, so owned types like `String` can be used.
+    type Content: Debug + Ord + Clone + AsRef<[u8]>;
+    /// The type of the hash of some content, which is used as the key of the stored claims.
+    type ContentHash: Debug + Ord + Clone;
+    /// The hashing algorithm used to hash the content of claims.
+    type Hasher: Hashing<Output = Self::ContentHash>;
+    /// The maximum number of claims a single account can own.
+    const MAX_CLAIMS: u32;
+    /// The type of the metadata which can be attached to a claim, for example a title.
+    type Metadata: Debug + Clone;
+    /// The maximum length of the content of a claim, in bytes. This keeps huge payloads from
+    /// bloating the state, even though only the hash of the content is stored.
+    const MAX_CONTENT_LEN: usize;
+    /// The number of blocks after which a claim expires, or `None` if claims never expire.
+    const EXPIRY_PERIOD: Option<Self::BlockNumber>;
+}
+
+/// The events which can be emitted by the Proof of Existence Module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<T: Config> {
+    /// `owner` created a claim on `claim`.
+    ClaimCreated {
+        owner: T::AccountId,
+        claim: T::Content,
+    },
+    /// `owner` revoked their claim on `claim`.
+    ClaimRevoked {
+        owner: T::AccountId,
+        claim: T::Content,
+    },
+    /// All the `count` claims of `owner` were revoked by root.
+    ClaimsRevoked { owner: T::AccountId, count: u32 },
+    /// The claim on `claim` was transferred from `from` to `to`.
+    ClaimTransferred {
+        from: T::AccountId,
+        to: T::AccountId,
+        claim: T::Content,
+    },
+}
+
+/// The initial state of the Proof of Existence Module, which is applied before block 1 is executed.
+#[derive(Debug, Clone)]
+pub struct GenesisConfig<T: Config> {
+    /// The initial claims, as pairs of content and owner.
+    pub claims: Vec<(T::Content, T::AccountId)>,
+}
+
+impl<T: Config> Default for GenesisConfig<T> {
+    fn default() -> Self {
+        Self { claims: Vec::new() }
+    }
+}
+
+/// The information stored about each claim.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T::AccountId: serde::Serialize, T::BlockNumber: serde::Serialize",
+        deserialize = "T::AccountId: serde::Deserialize<'de>, T::BlockNumber: serde::Deserialize<'de>"
+    ))
+)]
+pub struct ClaimInfo<T: Config> {
+    /// The account which owns the claim.
+    pub owner: T::AccountId,
+    /// The block number at which the claim was created.
+    pub created_at: T::BlockNumber,
+    /// The accounts approved by the owner to revoke the claim on their behalf. Only the owner can
+    /// transfer the claim.
+    pub approvers: BTreeSet<T::AccountId>,
 }
 
 /// This is the Proof of Existence Module.
 /// It is a simple module that allows accounts to claim existence of some data.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T::BlockNumber: serde::Serialize, T::Storage<T::ContentHash, ClaimInfo<T>>: serde::Serialize, T::Storage<T::AccountId, u32>: serde::Serialize, T::Storage<T::ContentHash, T::Metadata>: serde::Serialize",
+        deserialize = "T::BlockNumber: serde::Deserialize<'de>, T::Storage<T::ContentHash, ClaimInfo<T>>: serde::Deserialize<'de>, T::Storage<T::AccountId, u32>: serde::Deserialize<'de>, T::Storage<T::ContentHash, T::Metadata>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Pallet<T: Config> {
-    /// A simple storage map from content to the owner of that content.
+    /// A simple storage map from the hash of some content to the owner of that content, and when
+    /// it was claimed.
     /// Accounts can make multiple different claims, but each claim can only have one owner.
-    /* TODO: Add a field `claims` which is a `BTreeMap` fom `T::Content` to `T::AccountId`. */
-    claims: BTreeMap<T::Content, T::AccountId>,
+    claims: T::Storage<T::ContentHash, ClaimInfo<T>>,
+    /// A storage map from an account to the number of claims it owns.
+    claim_count: T::Storage<T::AccountId, u32>,
+    /// A storage map from the hash of some content to the metadata its owner attached to the
+    /// claim. Claims without metadata are not stored.
+    metadata: T::Storage<T::ContentHash, T::Metadata>,
+    /// The current block number, as provided by the runtime at the start of every block.
+    block_number: T::BlockNumber,
+    /// The events emitted by this module which have not been taken yet.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<Event<T>>,
+}
+
+impl<T: Config> Default for Pallet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Config> Pallet<T> {
     /// Create a new instance of the Proof of Existence Module.
     pub fn new() -> Self {
         Self {
-            claims: BTreeMap::new(),
+            claims: Default::default(),
+            claim_count: Default::default(),
+            metadata: Default::default(),
+            block_number: T::BlockNumber::zero(),
+            events: Vec::new(),
         }
     }
 
+    /// Apply the genesis `config` to this module, by creating the initial claims on behalf of their
+    /// owners.
+    pub fn build_genesis(&mut self, config: GenesisConfig<T>) -> DispatchResult {
+        for (claim, owner) in config.claims {
+            self.create_claim(owner, claim)?;
+        }
+        Ok(())
+    }
+
+    /// Get the metadata attached to the claim on `claim`, if any.
+    pub fn metadata(&self, claim: &T::Content) -> Option<&T::Metadata> {
+        self.metadata.get(&Self::hash_content(claim))
+    }
+
+    /// Check that `claim` is not longer than the maximum content length.
+    fn ensure_content_len(claim: &T::Content) -> DispatchResult {
+        if claim.as_ref().len() > T::MAX_CONTENT_LEN {
+            return Err(DispatchError::ContentTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Hash some content into the key under which its claim is stored.
+    pub fn hash_content(claim: &T::Content) -> T::ContentHash {
+        T::Hasher::hash(claim.as_ref())
+    }
+
     /// Get the owner (if any) of a claim.
     pub fn get_claim(&self, claim: &T::Content) -> Option<&T::AccountId> {
-        self.claims.get(claim)
+        self.get_claim_by_hash(&Self::hash_content(claim))
+    }
+
+    /// Check whether some content has been claimed.
+    /// Like `get_claim`, this includes expired claims which have not been claimed again yet.
+    pub fn exists(&self, claim: &T::Content) -> bool {
+        self.claims.contains_key(&Self::hash_content(claim))
+    }
+
+    /// Get the owner of a claim, or `default` if the content has not been claimed.
+    pub fn owner_or(&self, claim: &T::Content, default: T::AccountId) -> T::AccountId {
+        self.get_claim(claim).cloned().unwrap_or(default)
+    }
+
+    /// Get the owner (if any) of a claim, given the hash of its content.
+    pub fn get_claim_by_hash(&self, hash: &T::ContentHash) -> Option<&T::AccountId> {
+        self.claims.get(hash).map(|info| &info.owner)
+    }
+
+    /// Get all the information (if any) stored about a claim.
+    pub fn get_claim_info(&self, claim: &T::Content) -> Option<&ClaimInfo<T>> {
+        self.claims.get(&Self::hash_content(claim))
+    }
+
+    /// Get the owner (if any) of a claim, together with its age at the block number `now`, which
+    /// is the number of blocks since it was created.
+    pub fn claim_details(
+        &self,
+        claim: &T::Content,
+        now: T::BlockNumber,
+    ) -> Option<(&T::AccountId, T::BlockNumber)> {
+        let info = self.get_claim_info(claim)?;
+        let age = now
+            .checked_sub(&info.created_at)
+            .unwrap_or_else(T::BlockNumber::zero);
+        Some((&info.owner, age))
+    }
+
+    /// Get the hashes of all the contents claimed by an account `who`.
+    /// Claims are keyed by the hash of their content rather than by owner, so this is a linear scan
+    /// over all claims, and only the hashes are returned since the content itself is not stored.
+    pub fn claims_by_owner(&self, who: &T::AccountId) -> Vec<&T::ContentHash> {
+        self.claims
+            .iter()
+            .filter(|(_, info)| &info.owner == who)
+            .map(|(claim, _)| claim)
+            .collect()
+    }
+
+    /// Get a page of at most `limit` claims, skipping the first `start` claims.
+    /// Claims are ordered by the hash of their content, so pages are stable as long as no claims
+    /// are created or revoked in between.
+    pub fn claims_paged(
+        &self,
+        start: usize,
+        limit: usize,
+    ) -> Vec<(&T::ContentHash, &T::AccountId)> {
+        self.claims
+            .iter()
+            .skip(start)
+            .take(limit)
+            .map(|(hash, info)| (hash, &info.owner))
+            .collect()
+    }
+
+    /// Export all the claims as pairs of the hash of their content and their owner.
+    /// Only the hash of the content is stored, so the content itself cannot be exported. The
+    /// claims are ordered by the hash of their content, so the export does not depend on the order
+    /// in which the claims were created.
+    pub fn claims_ordered(&self) -> Vec<(T::ContentHash, T::AccountId)> {
+        self.claims
+            .iter()
+            .map(|(hash, info)| (hash.clone(), info.owner.clone()))
+            .collect()
+    }
+
+    /// Compute the root of a Merkle tree over the claims, as exported by `claims_ordered`, using
+    /// `Config::Hasher`. This can be used to prove that a claim is part of the state.
+    ///
+    /// Each leaf is the hash of a claim and its owner. Each parent is the hash of its two children,
+    /// and a node without a sibling is carried up to the next level as is. Like block headers, we
+    /// use the `Debug` representation as the encoding. The root of no claims is the hash of no
+    /// bytes.
+    pub fn claims_merkle_root(&self) -> T::ContentHash
+    where
+        T::AccountId: Debug,
+    {
+        let mut nodes = self
+            .claims_ordered()
+            .iter()
+            .map(|(hash, owner)| T::Hasher::hash(format!("{:?}/{:?}", hash, owner).as_bytes()))
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            return T::Hasher::hash(&[]);
+        }
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => T::Hasher::hash(format!("{:?}/{:?}", left, right).as_bytes()),
+                    [single] => single.clone(),
+                    _ => unreachable!("chunks have one or two nodes"),
+                })
+                .collect();
+        }
+        nodes.remove(0)
+    }
+
+    /// Check if a claim has expired at the block number `now`.
+    /// Returns `false` if the claim does not exist, or if claims never expire.
+    pub fn is_expired(&self, claim: &T::Content, now: T::BlockNumber) -> bool {
+        let info = self.claims.get(&Self::hash_content(claim));
+        let (Some(info), Some(period)) = (info, T::EXPIRY_PERIOD) else {
+            return false;
+        };
+        match info.created_at.checked_add(&period) {
+            Some(expires_at) => now >= expires_at,
+            None => false,
+        }
+    }
+
+    /// Check that `who` may revoke the claim on `claim`, because they own it or were approved by
+    /// its owner, and return the owner.
+    /// This function returns an error if the claim does not exist, or if `who` may not revoke it.
+    fn ensure_can_revoke(
+        &self,
+        claim: &T::Content,
+        who: &T::AccountId,
+    ) -> Result<T::AccountId, DispatchError> {
+        let info = self
+            .get_claim_info(claim)
+            .ok_or(DispatchError::NoSuchClaim)?;
+        if &info.owner != who && !info.approvers.contains(who) {
+            return Err(DispatchError::NotOwner);
+        }
+        Ok(info.owner.clone())
+    }
+
+    /// Get the events emitted by this module which have not been taken yet.
+    pub fn events(&self) -> &[Event<T>] {
+        &self.events
+    }
+
+    /// Take all the events emitted by this module, leaving no events behind.
+    pub fn take_events(&mut self) -> Vec<Event<T>> {
+        core::mem::take(&mut self.events)
+    }
+
+    /// Get the number of claims owned by an account `who`.
+    pub fn claims_of(&self, who: &T::AccountId) -> u32 {
+        *self.claim_count.get(who).unwrap_or(&0)
+    }
+
+    /// Remove all the claims owned by `owner`, returning how many were removed.
+    /// This is the unchecked part of `revoke_all`, and emits the same `ClaimsRevoked` event.
+    pub fn remove_claims_of(&mut self, owner: &T::AccountId) -> u32 {
+        let count = self.claims_of(owner);
+        for hash in self
+            .claims_by_owner(owner)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+        {
+            self.claims.remove(&hash);
+            self.metadata.remove(&hash);
+        }
+        self.claim_count.remove(owner);
+        self.events.push(Event::ClaimsRevoked {
+            owner: owner.clone(),
+            count,
+        });
+        count
+    }
+
+    /// Increase the number of claims owned by `who` by one.
+    fn inc_claim_count(&mut self, who: &T::AccountId) {
+        let count = self.claims_of(who);
+        self.claim_count.insert(who.clone(), count + 1);
+    }
+
+    /// Decrease the number of claims owned by `who` by one, removing the entry when it reaches
+    /// zero.
+    fn dec_claim_count(&mut self, who: &T::AccountId) {
+        match self.claims_of(who) {
+            0 | 1 => self.claim_count.remove(who),
+            count => self.claim_count.insert(who.clone(), count - 1),
+        };
+    }
+}
+
+impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+    /// The pallet only holds its own state, so the runtime provides the current block number at
+    /// the start of every block. It is used to record when claims are created.
+    fn on_initialize(&mut self, block_number: T::BlockNumber) {
+        self.block_number = block_number;
+    }
+}
+
+impl<T: Config + Debug> EncodeStorage for Pallet<T>
+where
+    T::AccountId: Debug,
+{
+    /// Everything but the events which have not been taken yet. The maps are encoded as lists of
+    /// key-value pairs, so the encoding does not depend on the storage backend.
+    fn encode_storage(&self) -> String {
+        format!(
+            "{:?}",
+            (
+                self.claims.iter().collect::<Vec<_>>(),
+                self.claim_count.iter().collect::<Vec<_>>(),
+                self.metadata.iter().collect::<Vec<_>>(),
+                &self.block_number
+            )
+        )
     }
 }
 
 #[macros::call]
 impl<T: Config> Pallet<T> {
     /// Create a new claim on behalf of the `caller`.
-    /// This function will return an error if someone already has claimed that content.
+    /// This function will return an error if the content is too large, if someone already has
+    /// claimed that content, or if the caller already owns the maximum number of claims.
+    /// Expired claims are open to anyone: creating one takes it away from its previous owner.
+    #[weight(10)]
     pub fn create_claim(&mut self, caller: T::AccountId, claim: T::Content) -> DispatchResult {
         /* TODO: Check that a `claim` does not already exist. If so, return an error. */
         /* TODO: `insert` the claim on behalf of `caller`. */
-        if self.claims.contains_key(&claim) {
-            return Err("this content is already claimed");
+        Self::ensure_content_len(&claim)?;
+        let key = Self::hash_content(&claim);
+        if self.claims.contains_key(&key) && !self.is_expired(&claim, self.block_number) {
+            return Err(DispatchError::ClaimAlreadyExists);
+        }
+        if self.claims_of(&caller) >= T::MAX_CLAIMS {
+            return Err(DispatchError::TooManyClaims);
+        }
+        self.metadata.remove(&key);
+        if let Some(expired) = self.claims.remove(&key) {
+            self.dec_claim_count(&expired.owner);
+            self.events.push(Event::ClaimRevoked {
+                owner: expired.owner,
+                claim: claim.clone(),
+            });
+        }
+        self.inc_claim_count(&caller);
+        self.claims.insert(
+            key,
+            ClaimInfo {
+                owner: caller.clone(),
+                created_at: self.block_number,
+                approvers: BTreeSet::new(),
+            },
+        );
+        self.events.push(Event::ClaimCreated {
+            owner: caller,
+            claim,
+        });
+        Ok(())
+    }
+
+    /// Create a new claim on behalf of the `caller` like `create_claim`, and attach `metadata` to
+    /// it.
+    #[weight(15)]
+    pub fn create_claim_with_metadata(
+        &mut self,
+        caller: T::AccountId,
+        claim: T::Content,
+        metadata: T::Metadata,
+    ) -> DispatchResult {
+        let key = Self::hash_content(&claim);
+        self.create_claim(caller, claim)?;
+        self.metadata.insert(key, metadata);
+        Ok(())
+    }
+
+    /// Set the metadata attached to an existing claim, replacing any previous metadata.
+    /// This function will return an error if the claim does not exist, or if the caller is not the
+    /// owner.
+    #[weight(5)]
+    pub fn set_metadata(
+        &mut self,
+        caller: T::AccountId,
+        claim: T::Content,
+        metadata: T::Metadata,
+    ) -> DispatchResult {
+        let owner = self.get_claim(&claim).ok_or(DispatchError::NoSuchClaim)?;
+        if owner != &caller {
+            return Err(DispatchError::NotOwner);
+        }
+        self.metadata.insert(Self::hash_content(&claim), metadata);
+        Ok(())
+    }
+
+    /// Create multiple new claims on behalf of the `caller`.
+    /// This function is atomic: all the claims are checked before any of them is inserted, so if
+    /// any claim cannot be created, none of them are.
+    #[weight(50)]
+    pub fn create_claims(
+        &mut self,
+        caller: T::AccountId,
+        claims: Vec<T::Content>,
+    ) -> DispatchResult {
+        let mut seen = BTreeSet::new();
+        for claim in &claims {
+            Self::ensure_content_len(claim)?;
+            let taken =
+                self.get_claim(claim).is_some() && !self.is_expired(claim, self.block_number);
+            if taken || !seen.insert(claim) {
+                return Err(DispatchError::ClaimAlreadyExists);
+            }
+        }
+        if self.claims_of(&caller) as usize + claims.len() > T::MAX_CLAIMS as usize {
+            return Err(DispatchError::TooManyClaims);
         }
-        self.claims.insert(claim, caller);
+        for claim in claims {
+            self.create_claim(caller.clone(), claim)?;
+        }
+        Ok(())
+    }
+
+    /// Approve the account `who` to revoke an existing claim of the `caller`.
+    /// This function will return an error if the claim does not exist, or if the caller is not the
+    /// owner.
+    #[weight(5)]
+    pub fn add_approver(
+        &mut self,
+        caller: T::AccountId,
+        claim: T::Content,
+        who: T::AccountId,
+    ) -> DispatchResult {
+        let info = self
+            .claims
+            .get_mut(&Self::hash_content(&claim))
+            .ok_or(DispatchError::NoSuchClaim)?;
+        if info.owner != caller {
+            return Err(DispatchError::NotOwner);
+        }
+        info.approvers.insert(who);
         Ok(())
     }
 
     /// Revoke an existing claim on some content.
-    /// This function should only succeed if the caller is the owner of an existing claim.
-    /// It will return an error if the claim does not exist, or if the caller is not the owner.
+    /// This function should only succeed if the caller is the owner of an existing claim, or was
+    /// approved by the owner, even if the claim has expired.
+    /// It will return an error if the claim does not exist, or if the caller is neither the owner
+    /// nor an approver.
+    #[weight(10)]
     pub fn revoke_claim(&mut self, caller: T::AccountId, claim: T::Content) -> DispatchResult {
-        let current_owner = self.get_claim(&claim).ok_or("Claim not existing")?;
+        let owner = self.ensure_can_revoke(&claim, &caller)?;
+        let key = Self::hash_content(&claim);
+        self.claims.remove(&key);
+        self.metadata.remove(&key);
+        self.dec_claim_count(&owner);
+        self.events.push(Event::ClaimRevoked { owner, claim });
+        Ok(())
+    }
+
+    /// Revoke multiple existing claims of the `caller`.
+    /// This function is atomic: all the claims are checked before any of them is revoked, so if
+    /// any claim cannot be revoked, none of them are.
+    #[weight(50)]
+    pub fn revoke_claims(
+        &mut self,
+        caller: T::AccountId,
+        claims: Vec<T::Content>,
+    ) -> DispatchResult {
+        let mut seen = BTreeSet::new();
+        for claim in &claims {
+            if !seen.insert(claim) {
+                return Err(DispatchError::NoSuchClaim);
+            }
+            self.ensure_can_revoke(claim, &caller)?;
+        }
+        for claim in claims {
+            self.revoke_claim(caller.clone(), claim)?;
+        }
+        Ok(())
+    }
+
+    /// Revoke an existing claim on some content, regardless of who owns it.
+    /// This function returns an error unless `caller` is the root account, or if the claim does
+    /// not exist.
+    #[origin(root)]
+    #[weight(10)]
+    pub fn force_revoke_claim(
+        &mut self,
+        caller: T::AccountId,
+        claim: T::Content,
+    ) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        let owner = self
+            .get_claim(&claim)
+            .ok_or(DispatchError::NoSuchClaim)?
+            .clone();
+        self.revoke_claim(owner, claim)
+    }
+
+    /// Revoke all the claims owned by `owner`, for example when the account is banned.
+    /// This function returns an error unless it is called with the root origin.
+    /// Claims are stored by the hash of their content, so a single `ClaimsRevoked` event with the
+    /// number of removed claims is emitted, instead of a `ClaimRevoked` event for every claim.
+    #[origin(root)]
+    #[weight(50)]
+    pub fn revoke_all(&mut self, caller: T::AccountId, owner: T::AccountId) -> DispatchResult {
+        crate::system::ensure_root::<T>(&caller)?;
+        self.remove_claims_of(&owner);
+        Ok(())
+    }
+
+    /// Transfer the ownership of an existing claim to `new_owner`.
+    /// This function should only succeed if the caller is the owner of an existing claim. The
+    /// approvers of the previous owner are removed.
+    /// It will return an error if the claim does not exist, if the caller is not the owner, or if
+    /// `new_owner` already owns `Config::MAX_CLAIMS` claims.
+    #[weight(10)]
+    pub fn transfer_claim(
+        &mut self,
+        caller: T::AccountId,
+        claim: T::Content,
+        new_owner: T::AccountId,
+    ) -> DispatchResult {
+        let current_owner = self.get_claim(&claim).ok_or(DispatchError::NoSuchClaim)?;
         if current_owner != &caller {
-            return Err(&"Cannot revoke claim that is not owned by caller");
+            return Err(DispatchError::NotOwner);
         }
-        self.claims.remove(&claim);
+        if new_owner != caller && self.claims_of(&new_owner) >= T::MAX_CLAIMS {
+            return Err(DispatchError::TooManyClaims);
+        }
+        self.dec_claim_count(&caller);
+        self.inc_claim_count(&new_owner);
+        if let Some(info) = self.claims.get_mut(&Self::hash_content(&claim)) {
+            info.owner = new_owner.clone();
+            info.approvers.clear();
+        }
+        self.events.push(Event::ClaimTransferred {
+            from: caller,
+            to: new_owner,
+            claim,
+        });
         Ok(())
     }
 }
@@ -64,18 +601,72 @@ impl<T: Config> Pallet<T> {
 #[cfg(test)]
 mod test {
 
-    use super::Pallet;
+    use super::{Call, Event, Pallet};
+    use crate::support::{
+        test::VecMap, Dispatch, DispatchError, EncodeStorage, Hooks, Origin, PostDispatchInfo,
+        Weight,
+    };
 
+    #[derive(Debug, PartialEq)]
     struct TestConfig;
 
     impl super::Config for TestConfig {
         type Content = &'static str;
+        type ContentHash = u64;
+        type Hasher = crate::support::DefaultHashing;
+        const MAX_CLAIMS: u32 = 2;
+        type Metadata = &'static str;
+        const MAX_CONTENT_LEN: usize = 16;
+        const EXPIRY_PERIOD: Option<u32> = Some(10);
     }
 
     impl crate::system::Config for TestConfig {
         type AccountId = &'static str;
         type BlockNumber = u32;
         type Nonce = u32;
+        type RuntimeEvent = ();
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> &'static str {
+            "root"
+        }
+    }
+
+    /// The same as `TestConfig`, but storing the state of the pallet in a different backend.
+    #[derive(Debug, PartialEq)]
+    struct VecMapConfig;
+
+    impl super::Config for VecMapConfig {
+        type Content = &'static str;
+        type ContentHash = u64;
+        type Hasher = crate::support::DefaultHashing;
+        const MAX_CLAIMS: u32 = 2;
+        type Metadata = &'static str;
+        const MAX_CONTENT_LEN: usize = 16;
+        const EXPIRY_PERIOD: Option<u32> = Some(10);
+    }
+
+    impl crate::system::Config for VecMapConfig {
+        type AccountId = &'static str;
+        type BlockNumber = u32;
+        type Nonce = u32;
+        type RuntimeEvent = ();
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = VecMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> &'static str {
+            "root"
+        }
+    }
+
+    #[test]
+    fn default_proof_of_existence() {
+        let pallet = Pallet::<TestConfig>::default();
+        assert!(pallet.claims_paged(0, 10).is_empty());
+        assert!(pallet.events().is_empty());
     }
 
     #[test]
@@ -87,20 +678,670 @@ mod test {
         let mut pallet = Pallet::<TestConfig>::new();
 
         assert_eq!(pallet.get_claim(&content), None);
-        assert_eq!(pallet.create_claim(alice, &content), Ok(()));
+        assert_eq!(pallet.create_claim(alice, content), Ok(()));
         assert_eq!(pallet.get_claim(&content), Some(&alice));
         assert_eq!(
-            pallet.create_claim(bob, &content),
-            Err("this content is already claimed")
+            pallet.create_claim(bob, content),
+            Err(DispatchError::ClaimAlreadyExists)
+        );
+        assert_eq!(
+            pallet.revoke_claim(alice, "something else"),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(
+            pallet.revoke_claim(bob, content),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(pallet.revoke_claim(alice, content), Ok(()));
+    }
+
+    #[test]
+    fn dispatch_generated_calls() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        let alice = "alice";
+
+        // Every callable function gets a `Call` variant with the same arguments, which dispatches to
+        // it and reports its declared weight.
+        let create = Call::<TestConfig>::create_claims {
+            claims: vec!["first", "second"],
+        };
+        assert_eq!(
+            pallet.dispatch(Origin::Signed(alice), create),
+            Ok(PostDispatchInfo {
+                actual_weight: Weight(50)
+            })
+        );
+        assert!(Call::<TestConfig>::call_names().contains(&"revoke_claims"));
+        let revoke = Call::<TestConfig>::revoke_claims {
+            claims: vec!["first", "second"],
+        };
+        assert_eq!(revoke.name(), "revoke_claims");
+        assert_eq!(
+            pallet.dispatch(Origin::Signed(alice), revoke),
+            Ok(PostDispatchInfo {
+                actual_weight: Weight(50)
+            })
+        );
+        assert_eq!(pallet.get_claim(&"first"), None);
+        assert_eq!(pallet.get_claim(&"second"), None);
+
+        // Failing calls return the error of the function.
+        let revoke = Call::<TestConfig>::revoke_claims {
+            claims: vec!["first"],
+        };
+        assert_eq!(
+            pallet.dispatch(Origin::Signed(alice), revoke),
+            Err(DispatchError::NoSuchClaim)
+        );
+    }
+
+    #[test]
+    fn revoke_claims_is_atomic() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        let alice = "alice";
+        let bob = "bob";
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(bob, "second"), Ok(()));
+
+        assert_eq!(
+            pallet.revoke_claims(alice, vec!["first", "second"]),
+            Err(DispatchError::NotOwner)
         );
         assert_eq!(
-            pallet.revoke_claim(alice, &"something else"),
-            Err("Claim not existing")
+            pallet.revoke_claims(alice, vec!["first", "first"]),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(pallet.get_claim(&"first"), Some(&alice));
+
+        assert_eq!(pallet.revoke_claims(alice, vec!["first"]), Ok(()));
+        assert_eq!(pallet.get_claim(&"first"), None);
+        assert_eq!(pallet.claims_of(&alice), 0);
+    }
+
+    #[test]
+    fn content_size_limit() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        let alice = "alice";
+
+        // The content may be exactly as long as the limit, but not longer.
+        let at_limit = "0123456789abcdef";
+        let over_limit = "0123456789abcdefg";
+        assert_eq!(
+            pallet.create_claim(alice, over_limit),
+            Err(DispatchError::ContentTooLarge)
+        );
+        assert_eq!(
+            pallet.create_claims(alice, vec!["small", over_limit]),
+            Err(DispatchError::ContentTooLarge)
+        );
+        assert_eq!(pallet.get_claim(&"small"), None);
+        assert_eq!(pallet.create_claim(alice, at_limit), Ok(()));
+        assert_eq!(pallet.get_claim(&at_limit), Some(&alice));
+    }
+
+    #[test]
+    fn claim_metadata() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        let alice = "alice";
+        let bob = "bob";
+
+        assert_eq!(
+            pallet.create_claim_with_metadata(alice, "document", "A title"),
+            Ok(())
+        );
+        assert_eq!(pallet.get_claim(&"document"), Some(&alice));
+        assert_eq!(pallet.metadata(&"document"), Some(&"A title"));
+
+        // Only the owner can set the metadata of a claim.
+        assert_eq!(pallet.set_metadata(alice, "document", "New title"), Ok(()));
+        assert_eq!(pallet.metadata(&"document"), Some(&"New title"));
+        assert_eq!(
+            pallet.set_metadata(bob, "document", "Stolen"),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(
+            pallet.set_metadata(alice, "unknown", "Title"),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(pallet.metadata(&"document"), Some(&"New title"));
+
+        // The metadata is removed together with the claim.
+        assert_eq!(pallet.revoke_claim(alice, "document"), Ok(()));
+        assert_eq!(pallet.metadata(&"document"), None);
+    }
+
+    #[test]
+    fn transfer_claim() {
+        let content = "something";
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+
+        assert_eq!(
+            pallet.transfer_claim(alice, content, bob),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(pallet.create_claim(alice, content), Ok(()));
+        assert_eq!(
+            pallet.transfer_claim(bob, content, bob),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(pallet.get_claim(&content), Some(&alice));
+
+        assert_eq!(pallet.transfer_claim(alice, content, bob), Ok(()));
+        assert_eq!(pallet.get_claim(&content), Some(&bob));
+        assert_eq!(
+            pallet.events().last(),
+            Some(&Event::ClaimTransferred {
+                from: alice,
+                to: bob,
+                claim: content
+            })
         );
         assert_eq!(
-            pallet.revoke_claim(bob, &content),
-            Err("Cannot revoke claim that is not owned by caller")
+            pallet.revoke_claim(alice, content),
+            Err(DispatchError::NotOwner)
         );
-        assert_eq!(pallet.revoke_claim(alice, &content), Ok(()));
+        assert_eq!(pallet.revoke_claim(bob, content), Ok(()));
+    }
+
+    #[test]
+    fn transfer_claim_to_an_account_with_too_many_claims() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim(alice, "document"), Ok(()));
+        assert_eq!(pallet.create_claim(bob, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(bob, "second"), Ok(()));
+
+        assert_eq!(
+            pallet.transfer_claim(alice, "document", bob),
+            Err(DispatchError::TooManyClaims)
+        );
+        assert_eq!(pallet.get_claim(&"document"), Some(&alice));
+        assert_eq!(pallet.claims_of(&alice), 1);
+        assert_eq!(pallet.claims_of(&bob), 2);
+
+        // An owner at the limit can still transfer a claim to themselves.
+        assert_eq!(pallet.transfer_claim(bob, "first", bob), Ok(()));
+
+        // Once bob revokes a claim, the transfer succeeds.
+        assert_eq!(pallet.revoke_claim(bob, "first"), Ok(()));
+        assert_eq!(pallet.transfer_claim(alice, "document", bob), Ok(()));
+        assert_eq!(pallet.claims_of(&alice), 0);
+        assert_eq!(pallet.claims_of(&bob), 2);
+    }
+
+    #[test]
+    fn approvers_can_revoke() {
+        let content = "something";
+        let alice = "alice";
+        let bob = "bob";
+        let charlie = "charlie";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(
+            pallet.add_approver(alice, content, bob),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(pallet.create_claim(alice, content), Ok(()));
+        assert_eq!(
+            pallet.add_approver(bob, content, bob),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(pallet.add_approver(alice, content, bob), Ok(()));
+        assert!(pallet
+            .get_claim_info(&content)
+            .unwrap()
+            .approvers
+            .contains(&bob));
+
+        // Approvers cannot transfer the claim, and random accounts cannot revoke it.
+        assert_eq!(
+            pallet.transfer_claim(bob, content, bob),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(
+            pallet.revoke_claim(charlie, content),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(
+            pallet.revoke_claims(charlie, vec![content]),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(pallet.get_claim(&content), Some(&alice));
+
+        // An approver revokes the claim on behalf of its owner.
+        assert_eq!(pallet.revoke_claim(bob, content), Ok(()));
+        assert_eq!(pallet.get_claim(&content), None);
+        assert_eq!(pallet.claims_of(&alice), 0);
+        assert_eq!(
+            pallet.take_events().last(),
+            Some(&Event::ClaimRevoked {
+                owner: alice,
+                claim: content
+            })
+        );
+
+        // Approvers are removed when the claim is transferred.
+        assert_eq!(pallet.create_claim(alice, content), Ok(()));
+        assert_eq!(pallet.add_approver(alice, content, bob), Ok(()));
+        assert_eq!(pallet.transfer_claim(alice, content, charlie), Ok(()));
+        assert_eq!(
+            pallet.revoke_claim(bob, content),
+            Err(DispatchError::NotOwner)
+        );
+    }
+
+    #[test]
+    fn force_revoke_requires_root() {
+        let content = "something";
+        let alice = "alice";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(
+            pallet.force_revoke_claim("root", content),
+            Err(DispatchError::NoSuchClaim)
+        );
+        assert_eq!(pallet.create_claim(alice, content), Ok(()));
+
+        // Not even the owner can use the root-only call.
+        assert_eq!(
+            pallet.force_revoke_claim(alice, content),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(pallet.get_claim(&content), Some(&alice));
+
+        assert_eq!(pallet.force_revoke_claim("root", content), Ok(()));
+        assert_eq!(pallet.get_claim(&content), None);
+        assert_eq!(pallet.claims_of(&alice), 0);
+    }
+
+    #[test]
+    fn revoke_all_claims_of_an_owner() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "second"), Ok(()));
+        assert_eq!(pallet.create_claim(bob, "third"), Ok(()));
+
+        assert_eq!(
+            pallet.dispatch(Origin::Signed(alice), Call::revoke_all { owner: alice }),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(pallet.claims_of(&alice), 2);
+
+        pallet.take_events();
+        assert!(pallet
+            .dispatch(Origin::Root, Call::revoke_all { owner: alice })
+            .is_ok());
+        assert_eq!(
+            pallet.take_events(),
+            vec![Event::ClaimsRevoked {
+                owner: alice,
+                count: 2
+            }]
+        );
+        for content in ["first", "second"] {
+            assert_eq!(pallet.get_claim(&content), None);
+        }
+        assert_eq!(pallet.claims_of(&alice), 0);
+        assert!(pallet.claims_by_owner(&alice).is_empty());
+
+        // The claims of other accounts are untouched.
+        assert_eq!(pallet.get_claim(&"third"), Some(&bob));
+        assert_eq!(pallet.claims_of(&bob), 1);
+
+        // The revoked content can be claimed again, and the owner is no longer at the limit.
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "second"), Ok(()));
+        assert_eq!(
+            pallet.revoke_all(alice, alice),
+            Err(DispatchError::BadOrigin)
+        );
+        assert_eq!(pallet.claims_of(&alice), 2);
+        assert_eq!(pallet.remove_claims_of(&alice), 2);
+        assert_eq!(pallet.remove_claims_of(&alice), 0);
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.revoke_all("root", alice), Ok(()));
+        assert_eq!(pallet.revoke_all("root", alice), Ok(()));
+        assert_eq!(
+            pallet.take_events().last(),
+            Some(&Event::ClaimsRevoked {
+                owner: alice,
+                count: 0
+            })
+        );
+    }
+
+    #[test]
+    fn count_claims() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.claims_of(&alice), 0);
+
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "second"), Ok(()));
+        assert_eq!(pallet.claims_of(&alice), 2);
+
+        // A failed claim does not change the count.
+        assert!(pallet.create_claim(bob, "first").is_err());
+        assert_eq!(pallet.claims_of(&bob), 0);
+
+        assert_eq!(pallet.revoke_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.claims_of(&alice), 1);
+
+        assert_eq!(pallet.transfer_claim(alice, "second", bob), Ok(()));
+        assert_eq!(pallet.claims_of(&alice), 0);
+        assert_eq!(pallet.claims_of(&bob), 1);
+    }
+
+    #[test]
+    fn max_claims() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "second"), Ok(()));
+        assert_eq!(
+            pallet.create_claim(alice, "third"),
+            Err(DispatchError::TooManyClaims)
+        );
+        assert_eq!(pallet.get_claim(&"third"), None);
+
+        // Another account can still claim.
+        assert_eq!(pallet.create_claim(bob, "third"), Ok(()));
+
+        // Revoking a claim frees up space for a new one.
+        assert_eq!(pallet.revoke_claim(alice, "first"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "fourth"), Ok(()));
+    }
+
+    #[test]
+    fn claim_records_block_number() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        pallet.on_initialize(3);
+        assert_eq!(pallet.create_claim(alice, "something"), Ok(()));
+
+        let info = pallet.get_claim_info(&"something").unwrap();
+        assert_eq!(info.owner, alice);
+        assert_eq!(info.created_at, 3);
+
+        // Transferring a claim keeps the block number at which it was created.
+        pallet.on_initialize(5);
+        assert_eq!(pallet.transfer_claim(alice, "something", bob), Ok(()));
+        let info = pallet.get_claim_info(&"something").unwrap();
+        assert_eq!(info.owner, bob);
+        assert_eq!(info.created_at, 3);
+        assert!(pallet.get_claim_info(&"nothing").is_none());
+    }
+
+    #[test]
+    fn exists_and_owner_or() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert!(!pallet.exists(&"something"));
+        assert_eq!(pallet.owner_or(&"something", "nobody"), "nobody");
+
+        assert_eq!(pallet.create_claim("alice", "something"), Ok(()));
+        assert!(pallet.exists(&"something"));
+        assert!(!pallet.exists(&"nothing"));
+        assert_eq!(pallet.owner_or(&"something", "nobody"), "alice");
+        assert_eq!(pallet.owner_or(&"nothing", "nobody"), "nobody");
+    }
+
+    #[test]
+    fn claim_details() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.claim_details(&"something", 5), None);
+
+        pallet.on_initialize(1);
+        assert_eq!(pallet.create_claim("alice", "something"), Ok(()));
+        pallet.on_initialize(5);
+        assert_eq!(pallet.claim_details(&"something", 5), Some((&"alice", 4)));
+        assert_eq!(pallet.claim_details(&"something", 0), Some((&"alice", 0)));
+    }
+
+    #[test]
+    fn expired_claim_can_be_reclaimed() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        pallet.on_initialize(1);
+        assert_eq!(pallet.create_claim(alice, "something"), Ok(()));
+        assert!(!pallet.is_expired(&"something", 10));
+        assert!(pallet.is_expired(&"something", 11));
+        assert!(!pallet.is_expired(&"nothing", 11));
+
+        // Before the claim expires, it cannot be claimed by someone else.
+        pallet.on_initialize(10);
+        assert_eq!(
+            pallet.create_claim(bob, "something"),
+            Err(DispatchError::ClaimAlreadyExists)
+        );
+
+        // Once expired, `bob` can claim it.
+        pallet.on_initialize(11);
+        assert_eq!(
+            pallet.revoke_claim(bob, "something"),
+            Err(DispatchError::NotOwner)
+        );
+        assert_eq!(pallet.create_claim(bob, "something"), Ok(()));
+        assert_eq!(pallet.get_claim(&"something"), Some(&bob));
+        assert_eq!(pallet.get_claim_info(&"something").unwrap().created_at, 11);
+        assert_eq!(pallet.claims_of(&alice), 0);
+        assert_eq!(pallet.claims_of(&bob), 1);
+    }
+
+    #[test]
+    fn claims_are_keyed_by_hash() {
+        let hash = Pallet::<TestConfig>::hash_content;
+        assert_ne!(hash(&"something"), hash(&"something else"));
+        assert_eq!(hash(&"something"), hash(&"something"));
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim("alice", "something"), Ok(()));
+        assert_eq!(
+            pallet.get_claim_by_hash(&hash(&"something")),
+            Some(&"alice")
+        );
+        assert_eq!(pallet.get_claim_by_hash(&hash(&"something else")), None);
+
+        // Identical content hashes to the same key, so it cannot be claimed twice.
+        assert_eq!(
+            pallet.create_claim("bob", "something"),
+            Err(DispatchError::ClaimAlreadyExists)
+        );
+    }
+
+    #[test]
+    fn paginate_claims() {
+        let mut pallet = Pallet::<TestConfig>::new();
+        for (owner, claim) in [
+            ("alice", "a"),
+            ("alice", "b"),
+            ("bob", "c"),
+            ("bob", "d"),
+            ("charlie", "e"),
+        ] {
+            assert_eq!(pallet.create_claim(owner, claim), Ok(()));
+        }
+
+        let first = pallet.claims_paged(0, 2);
+        let second = pallet.claims_paged(2, 2);
+        let third = pallet.claims_paged(4, 2);
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+        assert_eq!(third.len(), 1);
+        assert!(pallet.claims_paged(5, 2).is_empty());
+
+        // Together, the pages contain every claim exactly once, in order.
+        let all: Vec<_> = first.into_iter().chain(second).chain(third).collect();
+        assert_eq!(all, pallet.claims_paged(0, usize::MAX));
+        assert!(all.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn claims_merkle_root_is_deterministic() {
+        let claims = [
+            ("alice", "a"),
+            ("bob", "b"),
+            ("alice", "c"),
+            ("charlie", "d"),
+        ];
+        let mut forward = Pallet::<TestConfig>::new();
+        let mut backward = Pallet::<TestConfig>::new();
+        let empty_root = forward.claims_merkle_root();
+        for (owner, claim) in claims {
+            assert_eq!(forward.create_claim(owner, claim), Ok(()));
+        }
+        for (owner, claim) in claims.into_iter().rev() {
+            assert_eq!(backward.create_claim(owner, claim), Ok(()));
+        }
+
+        // The export and the root do not depend on the insertion order.
+        assert_eq!(forward.claims_ordered(), backward.claims_ordered());
+        assert_eq!(forward.claims_ordered().len(), 4);
+        assert_eq!(forward.claims_merkle_root(), backward.claims_merkle_root());
+        assert_ne!(forward.claims_merkle_root(), empty_root);
+
+        // The root commits to the owner of each claim.
+        assert_eq!(backward.transfer_claim("charlie", "d", "bob"), Ok(()));
+        assert_ne!(forward.claims_merkle_root(), backward.claims_merkle_root());
+    }
+
+    #[test]
+    fn claims_by_owner() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert!(pallet.claims_by_owner(&alice).is_empty());
+
+        assert_eq!(pallet.create_claim(alice, "b"), Ok(()));
+        assert_eq!(pallet.create_claim(bob, "c"), Ok(()));
+        assert_eq!(pallet.create_claim(alice, "a"), Ok(()));
+
+        let hash = Pallet::<TestConfig>::hash_content;
+        let mut alice_claims = [hash(&"a"), hash(&"b")];
+        alice_claims.sort();
+        assert_eq!(
+            pallet.claims_by_owner(&alice),
+            alice_claims.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(pallet.claims_by_owner(&bob), vec![&hash(&"c")]);
+    }
+
+    #[test]
+    fn create_claims_is_atomic() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim(bob, "third"), Ok(()));
+
+        // The third claim collides, so none of the claims are created.
+        assert_eq!(
+            pallet.create_claims(alice, vec!["first", "second", "third"]),
+            Err(DispatchError::ClaimAlreadyExists)
+        );
+        assert_eq!(pallet.get_claim(&"first"), None);
+        assert_eq!(pallet.get_claim(&"second"), None);
+        assert_eq!(pallet.claims_of(&alice), 0);
+
+        // Duplicates within the same batch also collide.
+        assert_eq!(
+            pallet.create_claims(alice, vec!["first", "first"]),
+            Err(DispatchError::ClaimAlreadyExists)
+        );
+        assert_eq!(pallet.get_claim(&"first"), None);
+
+        assert_eq!(pallet.create_claims(alice, vec!["first", "second"]), Ok(()));
+        assert_eq!(pallet.get_claim(&"first"), Some(&alice));
+        assert_eq!(pallet.get_claim(&"second"), Some(&alice));
+        assert_eq!(pallet.claims_of(&alice), 2);
+    }
+
+    #[test]
+    fn claim_events() {
+        let alice = "alice";
+        let bob = "bob";
+
+        let mut pallet = Pallet::<TestConfig>::new();
+        assert_eq!(pallet.create_claim(alice, "something"), Ok(()));
+        assert_eq!(
+            pallet.events(),
+            &[Event::ClaimCreated {
+                owner: alice,
+                claim: "something"
+            }]
+        );
+
+        // Failed calls do not emit events.
+        assert!(pallet.create_claim(bob, "something").is_err());
+        assert!(pallet.revoke_claim(bob, "something").is_err());
+        assert_eq!(pallet.events().len(), 1);
+
+        assert_eq!(pallet.revoke_claim(alice, "something"), Ok(()));
+        assert_eq!(
+            pallet.take_events(),
+            vec![
+                Event::ClaimCreated {
+                    owner: alice,
+                    claim: "something"
+                },
+                Event::ClaimRevoked {
+                    owner: alice,
+                    claim: "something"
+                },
+            ]
+        );
+        assert!(pallet.events().is_empty());
+    }
+
+    /// Run the same sequence of calls against a pallet, returning what was observed.
+    fn exercise<T>() -> Vec<String>
+    where
+        T: super::Config<Content = &'static str, Metadata = &'static str>
+            + crate::system::Config<AccountId = &'static str, BlockNumber = u32>
+            + core::fmt::Debug,
+    {
+        let alice = "alice";
+        let bob = "bob";
+        let mut pallet = Pallet::<T>::new();
+        let mut observed = Vec::new();
+        pallet.on_initialize(1);
+        observed.push(format!("{:?}", pallet.create_claim(alice, "first")));
+        observed.push(format!(
+            "{:?}",
+            pallet.create_claim_with_metadata(alice, "second", "note")
+        ));
+        observed.push(format!("{:?}", pallet.create_claim(alice, "third")));
+        observed.push(format!("{:?}", pallet.create_claim(bob, "first")));
+        observed.push(format!("{:?}", pallet.transfer_claim(alice, "first", bob)));
+        observed.push(format!("{:?}", pallet.claims_by_owner(&alice).len()));
+        observed.push(format!("{:?}", pallet.encode_storage()));
+        observed.push(format!("{:?}", pallet.revoke_all("root", alice)));
+        observed.push(format!("{:?}", pallet.metadata(&"second")));
+        observed.push(format!("{:?}", pallet.take_events()));
+        observed.push(format!("{:?}", pallet.encode_storage()));
+        observed
+    }
+
+    #[test]
+    fn storage_backends_behave_identically() {
+        let expected = exercise::<TestConfig>();
+        assert_eq!(expected[3], "Err(ClaimAlreadyExists)");
+        assert_eq!(exercise::<VecMapConfig>(), expected);
     }
 }
@@ -1,3 +1,8 @@
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, vec::Vec};
+use core::fmt::Debug;
+
+use num::{One, Zero};
+
 /// The most primitive representation of a Blockchain block.
 pub struct Block<Header, Extrinsic> {
     /// The block header contains metadata about the block.
@@ -6,27 +11,430 @@ pub struct Block<Header, Extrinsic> {
     pub extrinsics: Vec<Extrinsic>,
 }
 
-/// We are using an extremely simplified header which only contains the current block number.
+/// We are using an extremely simplified header which only contains the current block number, and
+/// the hash of the parent block which links this block to the rest of the chain.
 /// On a real blockchain, you would expect to also find:
-/// - parent block hash
 /// - state root
 /// - extrinsics root
 /// - etc...
-pub struct Header<BlockNumber> {
+pub struct Header<BlockNumber, Hash> {
     pub block_number: BlockNumber,
+    /// The hash of the previous block. The first block uses the default hash as its parent.
+    pub parent_hash: Hash,
+}
+
+impl<BlockNumber: Debug, Hash: Debug> Header<BlockNumber, Hash> {
+    /// Encode this header as bytes.
+    /// For simplicity, we use the `Debug` representation of the fields as their encoding.
+    pub fn encode(&self) -> Vec<u8> {
+        format!("{:?}/{:?}", self.block_number, self.parent_hash).into_bytes()
+    }
+
+    /// Compute the hash of this header with the hashing algorithm `H`.
+    /// This is used as the hash of the whole block.
+    pub fn hash<H: Hashing<Output = Hash>>(&self) -> Hash {
+        H::hash(&self.encode())
+    }
+}
+
+/// A trait for hashing algorithms, which turn any amount of bytes into a fixed-size `Output`.
+pub trait Hashing {
+    /// The type of the resulting hash.
+    type Output;
+
+    /// Hash the given `bytes`.
+    fn hash(bytes: &[u8]) -> Self::Output;
+}
+
+/// A simple hashing algorithm, using the deterministic `Fnv1aHasher`.
+/// This is good enough for our simple state machine, but is not a cryptographic hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultHashing;
+
+impl Hashing for DefaultHashing {
+    type Output = u64;
+
+    fn hash(bytes: &[u8]) -> u64 {
+        use core::hash::{Hash, Hasher};
+        let mut hasher = Fnv1aHasher::default();
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// The 64-bit FNV-1a hash function, which is simple, deterministic, and does not need the
+/// standard library, unlike `std::collections::hash_map::DefaultHasher`.
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Fnv1aHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl core::hash::Hasher for Fnv1aHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
 }
 
 /// This is an "extrinsic": literally an external message from outside of the blockchain.
-/// This simplified version of an extrinsic tells us who is making the call, and which call they are
-/// making.
-pub struct Extrinsic<Caller, Call> {
+/// This simplified version of an extrinsic tells us who is making the call, which call they are
+/// making, and the nonce of the caller used to prevent the extrinsic from being replayed.
+/// The caller can add an optional tip on top of the fee of the call, to have the extrinsic
+/// prioritized.
+/// The signature proves that the caller really made this call with this nonce and tip.
+/// With the `serde` feature, extrinsics can be serialized to send them over a wire or store them.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extrinsic<Caller, Call, Nonce, Signature, Tip> {
     pub caller: Caller,
+    pub nonce: Nonce,
     pub call: Call,
+    pub tip: Option<Tip>,
+    pub signature: Signature,
+}
+
+impl<Caller: core::hash::Hash, Call: Debug, Nonce: Debug, Tip: Debug>
+    Extrinsic<Caller, Call, Nonce, MockSignature, Tip>
+{
+    /// Create a new extrinsic without a tip, signed by `caller` using our mock signing scheme.
+    pub fn new_signed(caller: Caller, nonce: Nonce, call: Call) -> Self {
+        Self::new_signed_with_tip(caller, nonce, call, None)
+    }
+
+    /// Create a new extrinsic which pays `tip` on top of the fee of `call`, signed by `caller`
+    /// using our mock signing scheme.
+    pub fn new_signed_with_tip(caller: Caller, nonce: Nonce, call: Call, tip: Option<Tip>) -> Self {
+        let signature = MockSignature::sign(&caller, &signing_payload(&call, &nonce, &tip));
+        Self {
+            caller,
+            nonce,
+            call,
+            tip,
+            signature,
+        }
+    }
+}
+
+/// An extrinsic signed with our mock signing scheme, as assembled by a `BlockBuilder`.
+pub type MockExtrinsic<Caller, Call, Nonce, Tip> =
+    Extrinsic<Caller, Call, Nonce, MockSignature, Tip>;
+
+/// A helper to assemble a block of extrinsics signed with our mock signing scheme.
+///
+/// The nonce of each caller starts at zero, and is incremented for every extrinsic pushed on their
+/// behalf. Use `nonce` to start from a different nonce, for callers which already made
+/// transactions.
+pub struct BlockBuilder<BlockNumber, Hash, Caller, Call, Nonce, Tip> {
+    header: Header<BlockNumber, Hash>,
+    extrinsics: Vec<MockExtrinsic<Caller, Call, Nonce, Tip>>,
+    nonces: BTreeMap<Caller, Nonce>,
+}
+
+impl<BlockNumber, Hash, Caller, Call, Nonce, Tip>
+    BlockBuilder<BlockNumber, Hash, Caller, Call, Nonce, Tip>
+where
+    Hash: Default,
+    Caller: core::hash::Hash + Ord + Clone,
+    Call: Debug,
+    Nonce: Zero + One + Copy + Debug,
+    Tip: Debug,
+{
+    /// Start building the block `block_number`, whose parent hash is the default hash.
+    pub fn new(block_number: BlockNumber) -> Self {
+        Self {
+            header: Header {
+                block_number,
+                parent_hash: Hash::default(),
+            },
+            extrinsics: Vec::new(),
+            nonces: BTreeMap::new(),
+        }
+    }
+
+    /// Set the hash of the parent block.
+    pub fn parent_hash(mut self, parent_hash: Hash) -> Self {
+        self.header.parent_hash = parent_hash;
+        self
+    }
+
+    /// Set the nonce used by the next extrinsic of `caller`.
+    pub fn nonce(mut self, caller: Caller, nonce: Nonce) -> Self {
+        self.nonces.insert(caller, nonce);
+        self
+    }
+
+    /// Add an extrinsic which makes `call` on behalf of `caller`, signed with their next nonce.
+    pub fn push(self, caller: Caller, call: Call) -> Self {
+        self.push_with_tip(caller, call, None)
+    }
+
+    /// Add an extrinsic like `push`, which pays `tip` on top of the fee of `call`.
+    pub fn push_with_tip(mut self, caller: Caller, call: Call, tip: Option<Tip>) -> Self {
+        let nonce = self
+            .nonces
+            .entry(caller.clone())
+            .or_insert_with(Nonce::zero);
+        let extrinsic = Extrinsic::new_signed_with_tip(caller, *nonce, call, tip);
+        *nonce = *nonce + Nonce::one();
+        self.extrinsics.push(extrinsic);
+        self
+    }
+
+    /// Assemble the block.
+    pub fn build(
+        self,
+    ) -> Block<Header<BlockNumber, Hash>, MockExtrinsic<Caller, Call, Nonce, Tip>> {
+        Block {
+            header: self.header,
+            extrinsics: self.extrinsics,
+        }
+    }
+}
+
+/// The message which is signed by the caller of an extrinsic: the encoded call, nonce and tip.
+/// For simplicity, we use the `Debug` representation of the call, nonce and tip as their encoding.
+pub fn signing_payload<Call: Debug, Nonce: Debug, Tip: Debug>(
+    call: &Call,
+    nonce: &Nonce,
+    tip: &Option<Tip>,
+) -> Vec<u8> {
+    format!("{:?}/{:?}/{:?}", call, nonce, tip).into_bytes()
+}
+
+/// A trait for signatures which can be verified against the `Signer` of some message.
+pub trait Verify<Signer> {
+    /// Returns `true` if this is a valid signature of `message` by `signer`.
+    fn verify(&self, message: &[u8], signer: &Signer) -> bool;
+}
+
+/// A simple deterministic mock signature: the hash of the signer together with the message.
+/// Anyone can produce this signature, so it provides no security at all, but it allows us to
+/// exercise a real verification step in the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MockSignature(pub u64);
+
+impl MockSignature {
+    /// Sign `message` on behalf of `signer`.
+    pub fn sign<Signer: core::hash::Hash>(signer: &Signer, message: &[u8]) -> Self {
+        use core::hash::{Hash, Hasher};
+        let mut hasher = Fnv1aHasher::default();
+        signer.hash(&mut hasher);
+        message.hash(&mut hasher);
+        MockSignature(hasher.finish())
+    }
+}
+
+impl<Signer: core::hash::Hash> Verify<Signer> for MockSignature {
+    fn verify(&self, message: &[u8], signer: &Signer) -> bool {
+        *self == MockSignature::sign(signer, message)
+    }
+}
+
+/// The errors which can be returned by our runtime and its pallets.
+/// Using an enum instead of plain strings allows callers to match on specific failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DispatchError {
+    /// An account does not have enough funds for the requested operation.
+    InsufficientFunds,
+    /// The requested operation would drop an account below the existential deposit.
+    WouldKillAccount,
+    /// The requested operation would spend locked funds.
+    BalanceLocked,
+    /// An account is frozen, so it can neither send nor receive funds.
+    AccountFrozen,
+    /// An account does not have enough reserved balance for the requested operation.
+    InsufficientReserved,
+    /// A spender has not been approved to transfer enough funds on behalf of the owner.
+    InsufficientAllowance,
+    /// The amount of a transfer is zero.
+    ZeroAmount,
+    /// The amount of a transfer is below the minimum transfer amount.
+    BelowMinimumTransfer,
+    /// The sender and the recipient of a transfer are the same account.
+    SelfTransfer,
+    /// A mathematical operation overflowed.
+    Overflow,
+    /// A mathematical operation divided by zero.
+    DivisionByZero,
+    /// A mathematical operation underflowed.
+    Underflow,
+    /// The content is already claimed by some account.
+    ClaimAlreadyExists,
+    /// The content has not been claimed by any account.
+    NoSuchClaim,
+    /// The caller is not the owner of the claim.
+    NotOwner,
+    /// The caller already owns the maximum number of claims.
+    TooManyClaims,
+    /// The content of a claim is longer than the maximum content length.
+    ContentTooLarge,
+    /// The caller is not allowed to make this call.
+    BadOrigin,
+    /// An account has made the maximum number of transactions its nonce can count.
+    NonceOverflow,
+    /// A new timestamp is not greater than the current time.
+    TimestampNotIncreasing,
+    /// The nonce of an extrinsic does not match the expected nonce of the caller.
+    InvalidNonce,
+    /// The signature of an extrinsic does not match its caller, call and nonce.
+    InvalidSignature,
+    /// The call at the zero-based position `index` in a batch failed with `error`.
+    BatchInterrupted {
+        index: usize,
+        error: Box<DispatchError>,
+    },
+    /// The account does not exist, because it has no providers.
+    NoProviders,
+    /// Any other error, described by a static error message.
+    Other(&'static str),
+}
+
+impl core::fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DispatchError::InsufficientFunds => write!(f, "Not enough funds"),
+            DispatchError::WouldKillAccount => write!(f, "Would kill account"),
+            DispatchError::BalanceLocked => write!(f, "Balance is locked"),
+            DispatchError::AccountFrozen => write!(f, "Account frozen"),
+            DispatchError::InsufficientReserved => write!(f, "Not enough reserved balance"),
+            DispatchError::InsufficientAllowance => write!(f, "Not enough allowance"),
+            DispatchError::ZeroAmount => write!(f, "Cannot transfer zero"),
+            DispatchError::BelowMinimumTransfer => write!(f, "Amount below minimum"),
+            DispatchError::SelfTransfer => write!(f, "Cannot transfer to self"),
+            DispatchError::Overflow => write!(f, "Overflow"),
+            DispatchError::DivisionByZero => write!(f, "Division by zero"),
+            DispatchError::Underflow => write!(f, "Underflow"),
+            DispatchError::ClaimAlreadyExists => write!(f, "This content is already claimed"),
+            DispatchError::NoSuchClaim => write!(f, "Claim not existing"),
+            DispatchError::NotOwner => write!(f, "Caller is not the owner of the claim"),
+            DispatchError::TooManyClaims => write!(f, "Too many claims"),
+            DispatchError::ContentTooLarge => write!(f, "Content too large"),
+            DispatchError::BadOrigin => write!(f, "Bad origin"),
+            DispatchError::NonceOverflow => write!(f, "Nonce overflow"),
+            DispatchError::TimestampNotIncreasing => write!(f, "Timestamp must increase"),
+            DispatchError::InvalidNonce => write!(f, "Invalid nonce"),
+            DispatchError::InvalidSignature => write!(f, "Invalid signature"),
+            DispatchError::BatchInterrupted { index, error } => {
+                write!(f, "Batch interrupted at call {}: {}", index, error)
+            }
+            DispatchError::NoProviders => write!(f, "Account does not exist"),
+            DispatchError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<&'static str> for DispatchError {
+    fn from(message: &'static str) -> Self {
+        DispatchError::Other(message)
+    }
 }
 
 /// The Result type for our runtime. When everything completes successfully, we return `Ok(())`,
-/// otherwise return a static error message.
-pub type DispatchResult = Result<(), &'static str>;
+/// otherwise return a `DispatchError`.
+pub type DispatchResult = Result<(), DispatchError>;
+
+/// The error returned by the runtime when a block could not be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockError<Caller> {
+    /// The block itself is invalid, for example because it has an unexpected block number.
+    InvalidBlock(DispatchError),
+    /// The extrinsic at the zero-based position `index` in the block, submitted by `caller`,
+    /// failed with `error`.
+    /// If the error was returned by a pallet call, `pallet` is the name of that pallet. Otherwise
+    /// the extrinsic failed before its call was dispatched, for example because of a bad nonce.
+    ExtrinsicFailed {
+        index: usize,
+        caller: Caller,
+        pallet: Option<&'static str>,
+        error: DispatchError,
+    },
+}
+
+impl<Caller: core::fmt::Display> core::fmt::Display for BlockError<Caller> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BlockError::InvalidBlock(error) => write!(f, "Invalid block: {}", error),
+            BlockError::ExtrinsicFailed {
+                index,
+                caller,
+                pallet: Some(pallet),
+                error,
+            } => write!(
+                f,
+                "Extrinsic {} by {} failed in {}: {}",
+                index, caller, pallet, error
+            ),
+            BlockError::ExtrinsicFailed {
+                index,
+                caller,
+                pallet: None,
+                error,
+            } => write!(f, "Extrinsic {} by {} failed: {}", index, caller, error),
+        }
+    }
+}
+
+/// A summary of a block which was executed without stopping at failing extrinsics.
+#[derive(Debug, Clone)]
+pub struct BlockReport<Event> {
+    /// The events emitted by the extrinsics of the block, in order.
+    pub events: Vec<Event>,
+    /// The total weight actually consumed by the successful extrinsics.
+    pub total_weight: Weight,
+    /// The number of extrinsics which were applied successfully.
+    pub success_count: u32,
+    /// The zero-based position and the error of every extrinsic which failed.
+    pub failures: Vec<(usize, DispatchError)>,
+}
+
+/// The weight of a call is a measure of the resources needed to execute it.
+/// Blocks have a maximum weight, which limits how much work can be done in a single block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Weight(pub u64);
+
+impl Weight {
+    /// Add two weights together, saturating at the maximum weight instead of overflowing.
+    pub fn saturating_add(self, other: Weight) -> Weight {
+        Weight(self.0.saturating_add(other.0))
+    }
+}
+
+/// Information about a call which was dispatched successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PostDispatchInfo {
+    /// The weight actually consumed by the call.
+    pub actual_weight: Weight,
+}
+
+/// The Result type returned when dispatching a call. On success, this reports how much weight the
+/// call actually consumed.
+pub type DispatchResultWithInfo = Result<PostDispatchInfo, DispatchError>;
+
+/// A trait for calls which have a static weight.
+pub trait GetWeight {
+    /// The weight of executing this call.
+    fn weight(&self) -> Weight;
+}
+
+/// The origin of a call, which determines the privileges the call is made with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin<AccountId> {
+    /// The call was signed by the account `AccountId`, like every extrinsic.
+    Signed(AccountId),
+    /// The call is made with root privileges, which are needed for admin calls.
+    Root,
+    /// The call was not made by anyone in particular.
+    None,
+}
 
 /// A trait which allows us to dispatch an incoming extrinsic to the appropriate state transition
 /// function call.
@@ -36,7 +444,238 @@ pub trait Dispatch {
     /// The state transition function call the caller is trying to access.
     type Call;
 
-    /// This function takes a `caller` and the `call` they want to make, and returns a `Result`
-    /// based on the outcome of that function call.
-    fn dispatch(&mut self, caller: Self::Caller, call: Self::Call) -> DispatchResult;
+    /// This function takes the `origin` of the `call` they want to make, and returns a `Result`
+    /// based on the outcome of that function call, including the weight it consumed. Calls which
+    /// do not accept the `origin` return a `BadOrigin` error.
+    fn dispatch(
+        &mut self,
+        origin: Origin<Self::Caller>,
+        call: Self::Call,
+    ) -> DispatchResultWithInfo;
+}
+
+/// A trait which allows a runtime to charge a fee for every call, before it is dispatched.
+pub trait ChargeFee: Dispatch {
+    /// The type used to pay fees.
+    type Balance;
+
+    /// The fee which must be paid to execute `call`, which is its base fee plus the optional `tip`.
+    fn fee(call: &Self::Call, tip: &Option<Self::Balance>) -> Self::Balance;
+
+    /// Charge the fee of `call`, including `tip`, to `who`. This returns an error if `who` cannot
+    /// afford the fee, in which case the call must not be dispatched.
+    fn charge_fee(
+        &mut self,
+        who: &Self::Caller,
+        call: &Self::Call,
+        tip: &Option<Self::Balance>,
+    ) -> DispatchResult;
+
+    /// Check whether `who` can afford the fee of `call`, including `tip`, without charging it.
+    fn can_pay_fee(
+        &self,
+        who: &Self::Caller,
+        call: &Self::Call,
+        tip: &Option<Self::Balance>,
+    ) -> DispatchResult;
+}
+
+/// A trait which allows a runtime to reject a call before it is dispatched.
+///
+/// Pallets only hold their own state, so this is how a call can be checked against the state of
+/// other pallets, for example to only let accounts which exist in the system pallet own claims.
+pub trait CheckCall: Dispatch {
+    /// Check whether `call` may be dispatched with `origin`. This is also called for every call
+    /// of a batch, right before it is dispatched.
+    fn check_call(&self, origin: &Origin<Self::Caller>, call: &Self::Call) -> DispatchResult;
+}
+
+/// The outcome of validating a transaction for a transaction pool, without applying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidTransaction<Balance> {
+    /// The priority of the transaction, which is the fee it pays, including its tip. Pools should
+    /// include transactions with a higher priority first.
+    pub priority: Balance,
+    /// Whether the transaction can be applied right away. Transactions whose nonce is ahead of the
+    /// next nonce of their caller are valid, but must wait for the transactions before them.
+    pub ready: bool,
+}
+
+/// A key-value storage backend for the state of a pallet.
+///
+/// This covers the basic operations of a storage map, so code written against this trait works
+/// with any backend, for example an overlay which records changes for a rollback. The system and
+/// proof of existence pallets keep their maps in the backend chosen by `system::Config::Storage`.
+/// The balances pallet still uses `BTreeMap`s directly, since it also needs operations outside
+/// this trait, like `retain` or iterating over a range. The methods mirror those of `BTreeMap`,
+/// which implements this trait.
+pub trait StorageMap<K, V> {
+    /// Get a reference to the value stored under `key`, if any.
+    fn get(&self, key: &K) -> Option<&V>;
+    /// Get a mutable reference to the value stored under `key`, if any.
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+    /// Store `value` under `key`, returning the value previously stored there, if any.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+    /// Remove the value stored under `key`, returning it if it existed.
+    fn remove(&mut self, key: &K) -> Option<V>;
+    /// Iterate over all the stored key-value pairs, ordered by key.
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = (&'a K, &'a V)> + 'a>;
+    /// Check whether a value is stored under `key`.
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+impl<K: Ord, V> StorageMap<K, V> for BTreeMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BTreeMap::get_mut(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BTreeMap::remove(self, key)
+    }
+
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = (&'a K, &'a V)> + 'a> {
+        Box::new(BTreeMap::iter(self))
+    }
+}
+
+/// A trait for the runtime to react to the events of its pallets.
+///
+/// Pallets only hold their own state, so this is how a change in one pallet can update the state
+/// of another, for example creating an account in the system pallet when the balances pallet
+/// funds it.
+pub trait OnEvent<Event> {
+    /// Called by the runtime for every event emitted by a pallet, before it is deposited.
+    fn on_event(&mut self, event: &Event);
+}
+
+/// A trait which allows pallets to run logic at specific points in the lifecycle of a block.
+///
+/// Pallets only hold their own state, so this is how the runtime shares information like the
+/// current block number with them.
+pub trait Hooks<BlockNumber> {
+    /// Called by the runtime at the start of every block, before any extrinsic is executed.
+    fn on_initialize(&mut self, _block_number: BlockNumber) {}
+    /// Called by the runtime at the end of every block, after all its extrinsics were executed
+    /// successfully.
+    fn on_finalize(&mut self, _block_number: BlockNumber) {}
+}
+
+/// A trait for pallets to encode their persistent storage.
+///
+/// The runtime hashes this encoding to compute its state root, so it must leave out transient
+/// state like the events of the current block, which is not part of the state of the chain.
+pub trait EncodeStorage {
+    /// Encode the persistent storage of this pallet, in a deterministic way.
+    fn encode_storage(&self) -> String;
+}
+
+/// Serialize a `BTreeMap` as a list of key-value pairs.
+///
+/// JSON only supports string keys in maps, so maps with other keys, like tuples, can use this
+/// with `#[serde(with = "crate::support::map_as_pairs")]`.
+#[cfg(feature = "serde")]
+pub mod map_as_pairs {
+    use alloc::{collections::BTreeMap, vec::Vec};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K: Serialize, V: Serialize, S: Serializer>(
+        map: &BTreeMap<K, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let pairs = Vec::<(K, V)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::StorageMap;
+    use std::collections::BTreeMap;
+
+    /// A minimal in-memory storage backend, which keeps its entries in a vector sorted by key.
+    /// The pallet tests use it to check that they do not depend on the backend.
+    #[derive(Debug, Clone)]
+    pub(crate) struct VecMap<K, V>(Vec<(K, V)>);
+
+    impl<K, V> Default for VecMap<K, V> {
+        fn default() -> Self {
+            Self(Vec::new())
+        }
+    }
+
+    impl<K: Ord, V> StorageMap<K, V> for VecMap<K, V> {
+        fn get(&self, key: &K) -> Option<&V> {
+            let index = self.0.binary_search_by(|(k, _)| k.cmp(key)).ok()?;
+            Some(&self.0[index].1)
+        }
+
+        fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+            let index = self.0.binary_search_by(|(k, _)| k.cmp(key)).ok()?;
+            Some(&mut self.0[index].1)
+        }
+
+        fn insert(&mut self, key: K, value: V) -> Option<V> {
+            match self.0.binary_search_by(|(k, _)| k.cmp(&key)) {
+                Ok(index) => Some(core::mem::replace(&mut self.0[index].1, value)),
+                Err(index) => {
+                    self.0.insert(index, (key, value));
+                    None
+                }
+            }
+        }
+
+        fn remove(&mut self, key: &K) -> Option<V> {
+            let index = self.0.binary_search_by(|(k, _)| k.cmp(key)).ok()?;
+            Some(self.0.remove(index).1)
+        }
+
+        fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = (&'a K, &'a V)> + 'a> {
+            Box::new(self.0.iter().map(|(k, v)| (k, v)))
+        }
+    }
+
+    /// Run the same sequence of operations against a storage backend, returning what was observed.
+    fn exercise<S: StorageMap<&'static str, u32>>(storage: &mut S) -> Vec<String> {
+        let mut observed = Vec::new();
+        observed.push(format!("{:?}", storage.insert("bob", 10)));
+        observed.push(format!("{:?}", storage.insert("alice", 20)));
+        observed.push(format!("{:?}", storage.insert("bob", 30)));
+        if let Some(value) = storage.get_mut(&"alice") {
+            *value += 1;
+        }
+        observed.push(format!("{:?}", storage.get(&"alice")));
+        observed.push(format!("{:?}", storage.contains_key(&"charlie")));
+        observed.push(format!("{:?}", storage.remove(&"charlie")));
+        observed.push(format!("{:?}", storage.iter().collect::<Vec<_>>()));
+        observed.push(format!("{:?}", storage.remove(&"bob")));
+        observed.push(format!("{:?}", storage.iter().collect::<Vec<_>>()));
+        observed
+    }
+
+    #[test]
+    fn storage_backends_behave_identically() {
+        let expected = exercise(&mut BTreeMap::new());
+        assert_eq!(expected[2], "Some(10)");
+        assert_eq!(expected[6], r#"[("alice", 21), ("bob", 30)]"#);
+        assert_eq!(exercise(&mut VecMap::default()), expected);
+    }
 }
@@ -1,23 +1,114 @@
 /* TODO: You might need to update your imports. */
 
-use std::{collections::BTreeMap, fmt::Debug, ops::AddAssign};
+use alloc::{format, string::String, vec::Vec};
+use core::{fmt::Debug, ops::AddAssign};
 
-use num::{One, Zero};
+use num::{CheckedAdd, CheckedSub, One, Zero};
+
+use crate::support::{DispatchError, DispatchResult, EncodeStorage, Hooks, StorageMap, Weight};
 
 pub trait Config {
     type AccountId: Ord + Clone;
-    type BlockNumber: Zero + One + AddAssign + Copy + Debug;
-    type Nonce: Zero + One + Copy + Debug;
+    type BlockNumber: Zero + One + AddAssign + CheckedAdd + CheckedSub + Copy + Debug + Ord;
+    type Nonce: Zero + One + CheckedAdd + Copy + Debug + PartialOrd;
+    /// The type of a block hash. Hashes are small values which are copied around and compared,
+    /// so we only require `Copy + Debug + PartialEq`. The `Default` hash is used as the parent
+    /// hash of the first block.
+    type Hash: Copy + Debug + PartialEq + Default;
+    /// The hashing algorithm used to compute block hashes.
+    type Hashing: crate::support::Hashing<Output = Self::Hash>;
+    /// The storage backend of the maps kept by this pallet and the proof of existence pallet.
+    /// This is usually `BTreeMap`, but any `StorageMap` works, for example an overlay.
+    type Storage<K: Ord, V>: StorageMap<K, V> + Default;
+    /// The maximum total weight of the calls in a single block.
+    const MAX_BLOCK_WEIGHT: Weight;
+    /// The aggregated event type of the runtime, which wraps the events of every pallet.
+    type RuntimeEvent: Debug + Clone;
+    /// The privileged root account, which is allowed to make admin calls in any pallet.
+    fn root() -> Self::AccountId;
+}
+
+/// Ensure that `who` is the root account, returning a `BadOrigin` error otherwise.
+///
+/// Pallets gate their admin calls by starting them with `system::ensure_root::<T>(&caller)?`,
+/// which is equivalent to checking `caller == T::root()`.
+pub fn ensure_root<T: Config>(who: &T::AccountId) -> DispatchResult {
+    if who != &T::root() {
+        return Err(DispatchError::BadOrigin);
+    }
+    Ok(())
+}
+
+/// The events which can be emitted by the System Pallet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<T: Config> {
+    /// The account `who` was created, because it gained its first provider.
+    NewAccount { who: T::AccountId },
+    /// The account `who` was removed, because it lost its last provider.
+    KilledAccount { who: T::AccountId },
+}
+
+/// The initial state of the System Pallet, which is applied before block 1 is executed.
+#[derive(Debug, Clone)]
+pub struct GenesisConfig<T: Config> {
+    /// The block number the chain starts at. The first block to execute is the next one.
+    pub block_number: T::BlockNumber,
+}
+
+impl<T: Config> Default for GenesisConfig<T> {
+    fn default() -> Self {
+        Self {
+            block_number: T::BlockNumber::zero(),
+        }
+    }
 }
 
 /// This is the System Pallet.
 /// It handles low level state needed for your blockchain.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T::BlockNumber: serde::Serialize, T::Storage<T::AccountId, T::Nonce>: serde::Serialize, T::Storage<T::BlockNumber, T::Hash>: serde::Serialize, T::Storage<T::AccountId, u32>: serde::Serialize",
+        deserialize = "T::BlockNumber: serde::Deserialize<'de>, T::Storage<T::AccountId, T::Nonce>: serde::Deserialize<'de>, T::Storage<T::BlockNumber, T::Hash>: serde::Deserialize<'de>, T::Storage<T::AccountId, u32>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Pallet<T: Config> {
     /// The current block number.
     block_number: T::BlockNumber,
     /// A map from an account to their nonce.
-    nonce: BTreeMap<T::AccountId, T::Nonce>,
+    nonce: T::Storage<T::AccountId, T::Nonce>,
+    /// The events deposited during the current block, in the order they were emitted.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<T::RuntimeEvent>,
+    /// A map from a block number to the hash of that block.
+    block_hash: T::Storage<T::BlockNumber, T::Hash>,
+    /// The number of extrinsics applied in the current block.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    extrinsic_count: u32,
+    /// The total weight actually consumed by the extrinsics applied in the current block.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    block_weight: Weight,
+    /// A map from an account to the number of modules which provide for its existence, like the
+    /// balances module does for funded accounts. An account exists while it has any providers.
+    providers: T::Storage<T::AccountId, u32>,
+    /// A map from an account to the number of modules which depend on its existence, for example
+    /// to hold some of its state.
+    consumers: T::Storage<T::AccountId, u32>,
+    /// The number of accounts known to this module, which are the accounts with a nonce or a
+    /// provider. This is kept as a counter, so it can be read without scanning the maps.
+    account_count: u32,
+    /// The events emitted by this module which have not been taken yet. Unlike `events`, these
+    /// are only the events of the system pallet itself.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending_events: Vec<Event<T>>,
+}
+
+impl<T: Config> Default for Pallet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Config> Pallet<T> {
@@ -26,15 +117,40 @@ impl<T: Config> Pallet<T> {
         /* TODO: Return a new instance of the `Pallet` struct. */
         Self {
             block_number: T::BlockNumber::zero(),
-            nonce: BTreeMap::new(),
+            nonce: Default::default(),
+            events: Vec::new(),
+            block_hash: Default::default(),
+            extrinsic_count: 0,
+            block_weight: Weight(0),
+            providers: Default::default(),
+            consumers: Default::default(),
+            account_count: 0,
+            pending_events: Vec::new(),
         }
     }
+    /// Apply the genesis `config` to this pallet, which sets the starting block number.
+    pub fn build_genesis(&mut self, config: GenesisConfig<T>) -> DispatchResult {
+        self.set_block_number(config.block_number);
+        Ok(())
+    }
+
     /// Get the current block number.
     pub fn block_number(&self) -> T::BlockNumber {
         /* TODO: Return the current block number. */
         self.block_number
     }
 
+    /// Set the current block number, for example to start a chain from a block other than zero.
+    /// The next block to be executed is expected to have the number `block_number + 1`.
+    pub fn set_block_number(&mut self, block_number: T::BlockNumber) {
+        self.block_number = block_number;
+    }
+
+    /// Get the number of the block after the current one, or `None` if it would overflow.
+    pub fn next_block_number(&self) -> Option<T::BlockNumber> {
+        self.block_number.checked_add(&T::BlockNumber::one())
+    }
+
     // This function can be used to increment the block number.
     // Increases the block number by one.
     pub fn inc_block_number(&mut self) {
@@ -42,30 +158,280 @@ impl<T: Config> Pallet<T> {
         self.block_number += T::BlockNumber::one();
     }
 
+    /// Get the nonce of an account `who`.
+    /// If the account has no stored nonce, we return zero.
+    pub fn nonce(&self, who: &T::AccountId) -> T::Nonce {
+        *self.nonce.get(who).unwrap_or(&T::Nonce::zero())
+    }
+
+    /// Iterate over all accounts and their nonces, ordered by account.
+    pub fn nonces(&self) -> impl Iterator<Item = (&T::AccountId, &T::Nonce)> {
+        self.nonce.iter()
+    }
+
     // Increment the nonce of an account. This helps us keep track of how many transactions each
     // account has made.
-    pub fn inc_nonce(&mut self, who: &T::AccountId) {
+    // Returns an error if the account has already made the maximum number of transactions.
+    pub fn inc_nonce(&mut self, who: &T::AccountId) -> DispatchResult {
         /* TODO: Get the current nonce of `who`, and increment it by one. */
-        let current_nonce = self.nonce.get(who);
-        match current_nonce {
-            Some(current_nonce) => self
-                .nonce
-                .insert(who.clone(), *current_nonce + T::Nonce::one()),
-            None => self.nonce.insert(who.clone(), T::Nonce::one()),
+        let new_nonce = self
+            .nonce(who)
+            .checked_add(&T::Nonce::one())
+            .ok_or(DispatchError::NonceOverflow)?;
+        let known = self.is_known(who);
+        self.nonce.insert(who.clone(), new_nonce);
+        self.update_account_count(known, who);
+        Ok(())
+    }
+
+    /// Store the `hash` of the block with number `block_number`.
+    pub fn set_block_hash(&mut self, block_number: T::BlockNumber, hash: T::Hash) {
+        self.block_hash.insert(block_number, hash);
+    }
+
+    /// Get the hash of the block with number `block_number`, if it is known.
+    pub fn block_hash(&self, block_number: T::BlockNumber) -> Option<T::Hash> {
+        self.block_hash.get(&block_number).copied()
+    }
+
+    /// Get the events deposited during the current block.
+    pub fn events(&self) -> &[T::RuntimeEvent] {
+        &self.events
+    }
+
+    /// Deposit an `event` which happened during the current block.
+    pub fn deposit_event(&mut self, event: T::RuntimeEvent) {
+        self.events.push(event);
+    }
+
+    /// Take all the events emitted by this module, leaving no events behind.
+    /// The runtime wraps them in its own event type, and deposits them like any other event.
+    pub fn take_events(&mut self) -> Vec<Event<T>> {
+        core::mem::take(&mut self.pending_events)
+    }
+
+    /// Remove all the deposited events, which is done at the start of every block.
+    pub fn reset_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// Get the number of extrinsics applied in the current block.
+    pub fn extrinsic_count(&self) -> u32 {
+        self.extrinsic_count
+    }
+
+    /// Increase the number of extrinsics applied in the current block by one.
+    pub fn inc_extrinsic_count(&mut self) {
+        self.extrinsic_count += 1;
+    }
+
+    /// Reset the number of applied extrinsics, which is done at the start of every block.
+    pub fn reset_extrinsic_count(&mut self) {
+        self.extrinsic_count = 0;
+    }
+
+    /// Get the total weight actually consumed by the extrinsics applied in the current block.
+    pub fn block_weight(&self) -> Weight {
+        self.block_weight
+    }
+
+    /// Add the weight actually consumed by an extrinsic to the weight of the current block.
+    pub fn register_weight(&mut self, weight: Weight) {
+        self.block_weight = self.block_weight.saturating_add(weight);
+    }
+
+    /// Check that `nonce` is the next expected nonce of the account `who`.
+    /// The first transaction of an account is expected to use a nonce of zero.
+    pub fn check_nonce(&self, who: &T::AccountId, nonce: T::Nonce) -> DispatchResult {
+        if nonce != self.nonce(who) {
+            return Err(DispatchError::InvalidNonce);
+        }
+        Ok(())
+    }
+
+    /// Remove all the system state of the account `who`, so its nonce reads as zero again.
+    /// This is the system counterpart of removing an account from the balances pallet.
+    pub fn kill_account(&mut self, who: &T::AccountId) {
+        let known = self.is_known(who);
+        self.nonce.remove(who);
+        if self.providers.remove(who).is_some() {
+            self.pending_events
+                .push(Event::KilledAccount { who: who.clone() });
+        }
+        self.consumers.remove(who);
+        self.update_account_count(known, who);
+    }
+
+    /// Get the number of accounts known to this module, which are the accounts which have made a
+    /// transaction or exist. Accounts which were funded but never made a transaction are counted.
+    pub fn account_count(&self) -> u32 {
+        self.account_count
+    }
+
+    /// Check whether the account `who` has a nonce or a provider.
+    fn is_known(&self, who: &T::AccountId) -> bool {
+        self.nonce.contains_key(who) || self.providers.contains_key(who)
+    }
+
+    /// Update the account count after a change to the account `who`, which was `known` before.
+    fn update_account_count(&mut self, known: bool, who: &T::AccountId) {
+        match (known, self.is_known(who)) {
+            (false, true) => self.account_count += 1,
+            (true, false) => self.account_count -= 1,
+            _ => {}
+        }
+    }
+
+    /// Get the number of providers of the account `who`.
+    pub fn providers(&self, who: &T::AccountId) -> u32 {
+        *self.providers.get(who).unwrap_or(&0)
+    }
+
+    /// Get the number of consumers of the account `who`.
+    pub fn consumers(&self, who: &T::AccountId) -> u32 {
+        *self.consumers.get(who).unwrap_or(&0)
+    }
+
+    /// Check whether the account `who` exists, which is the case while it has any providers.
+    pub fn account_exists(&self, who: &T::AccountId) -> bool {
+        self.providers(who) > 0
+    }
+
+    /// Add a provider to the account `who`, which creates the account if it has no providers yet.
+    pub fn inc_providers(&mut self, who: &T::AccountId) {
+        let known = self.is_known(who);
+        let providers = self.providers(who);
+        self.providers.insert(who.clone(), providers + 1);
+        if providers == 0 {
+            self.pending_events
+                .push(Event::NewAccount { who: who.clone() });
+        }
+        self.update_account_count(known, who);
+    }
+
+    /// Remove a provider from the account `who`, removing the entry when it reaches zero.
+    pub fn dec_providers(&mut self, who: &T::AccountId) {
+        let known = self.is_known(who);
+        match self.providers(who) {
+            0 => None,
+            1 => {
+                self.pending_events
+                    .push(Event::KilledAccount { who: who.clone() });
+                self.providers.remove(who)
+            }
+            providers => self.providers.insert(who.clone(), providers - 1),
+        };
+        self.update_account_count(known, who);
+    }
+
+    /// Add a consumer to the account `who`.
+    /// Returns an error if the account does not exist, since only existing accounts can hold
+    /// state like claims or locks.
+    pub fn inc_consumers(&mut self, who: &T::AccountId) -> DispatchResult {
+        if !self.account_exists(who) {
+            return Err(DispatchError::NoProviders);
+        }
+        let consumers = self.consumers(who);
+        self.consumers.insert(who.clone(), consumers + 1);
+        Ok(())
+    }
+
+    /// Remove a consumer from the account `who`, removing the entry when it reaches zero.
+    pub fn dec_consumers(&mut self, who: &T::AccountId) {
+        match self.consumers(who) {
+            0 | 1 => self.consumers.remove(who),
+            consumers => self.consumers.insert(who.clone(), consumers - 1),
         };
     }
 }
 
+impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+    /// The runtime starts every block by moving the system pallet to the new block number.
+    /// Events, the extrinsic count and the block weight are stored per block, so they are reset
+    /// here.
+    fn on_initialize(&mut self, block_number: T::BlockNumber) {
+        self.block_number = block_number;
+        self.reset_events();
+        self.reset_extrinsic_count();
+        self.block_weight = Weight(0);
+    }
+}
+
+impl<T: Config> EncodeStorage for Pallet<T>
+where
+    T::AccountId: Debug,
+{
+    /// The events, the extrinsic count and the block weight only describe the current block, so
+    /// they are not encoded. The maps are encoded as lists of key-value pairs, so the encoding does
+    /// not depend on the storage backend.
+    fn encode_storage(&self) -> String {
+        format!(
+            "{:?}",
+            (
+                &self.block_number,
+                self.nonce.iter().collect::<Vec<_>>(),
+                self.block_hash.iter().collect::<Vec<_>>(),
+                self.providers.iter().collect::<Vec<_>>(),
+                self.consumers.iter().collect::<Vec<_>>(),
+                self.account_count,
+            )
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::Config;
+    use super::Event;
     use super::Pallet;
+    use crate::support::{test::VecMap, DispatchError, EncodeStorage, Hooks, Weight};
 
+    #[derive(Debug, PartialEq)]
     struct TestConfig;
     impl Config for TestConfig {
         type AccountId = String;
         type BlockNumber = u32;
         type Nonce = u32;
+        type RuntimeEvent = &'static str;
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> String {
+            "root".to_string()
+        }
+    }
+
+    struct SmallNonceConfig;
+    impl Config for SmallNonceConfig {
+        type AccountId = String;
+        type BlockNumber = u32;
+        type Nonce = u8;
+        type RuntimeEvent = &'static str;
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> String {
+            "root".to_string()
+        }
+    }
+
+    /// The same as `TestConfig`, but storing the state of the pallet in a different backend.
+    #[derive(Debug, PartialEq)]
+    struct VecMapConfig;
+    impl Config for VecMapConfig {
+        type AccountId = String;
+        type BlockNumber = u32;
+        type Nonce = u32;
+        type RuntimeEvent = &'static str;
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = VecMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> String {
+            "root".to_string()
+        }
     }
 
     #[test]
@@ -81,7 +447,278 @@ mod test {
         system.inc_block_number();
         assert_eq!(system.block_number(), 1);
 
-        system.inc_nonce(&"alice".to_string());
-        assert_eq!(*system.nonce.get(&"alice".to_string()).unwrap(), 1);
+        assert_eq!(system.inc_nonce(&"alice".to_string()), Ok(()));
+        assert_eq!(system.nonce(&"alice".to_string()), 1);
+        assert_eq!(system.nonce(&"bob".to_string()), 0);
+    }
+
+    #[test]
+    fn set_block_number() {
+        let mut system = Pallet::<TestConfig>::new();
+        system.set_block_number(100);
+        assert_eq!(system.block_number(), 100);
+
+        system.inc_block_number();
+        assert_eq!(system.block_number(), 101);
+    }
+
+    #[test]
+    fn default_system() {
+        let system = Pallet::<TestConfig>::default();
+        assert_eq!(system.block_number(), 0);
+        assert_eq!(system.account_count(), 0);
+    }
+
+    #[test]
+    fn check_nonce() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+
+        assert_eq!(system.check_nonce(&alice, 0), Ok(()));
+        assert_eq!(
+            system.check_nonce(&alice, 1),
+            Err(DispatchError::InvalidNonce)
+        );
+
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(
+            system.check_nonce(&alice, 0),
+            Err(DispatchError::InvalidNonce)
+        );
+        assert_eq!(system.check_nonce(&alice, 1), Ok(()));
+    }
+
+    #[test]
+    fn iterate_nonces() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        assert_eq!(system.nonces().count(), 0);
+
+        assert_eq!(system.inc_nonce(&bob), Ok(()));
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(system.inc_nonce(&bob), Ok(()));
+
+        let nonces = system.nonces().collect::<Vec<_>>();
+        assert_eq!(nonces, vec![(&alice, &1), (&bob, &2)]);
+    }
+
+    #[test]
+    fn ensure_root() {
+        assert_eq!(
+            super::ensure_root::<TestConfig>(&"root".to_string()),
+            Ok(())
+        );
+        assert_eq!(
+            super::ensure_root::<TestConfig>(&"alice".to_string()),
+            Err(DispatchError::BadOrigin)
+        );
+    }
+
+    #[test]
+    fn nonce_overflow() {
+        let mut system = Pallet::<SmallNonceConfig>::new();
+        let alice = "alice".to_string();
+
+        for _ in 0..u8::MAX {
+            assert_eq!(system.inc_nonce(&alice), Ok(()));
+        }
+        assert_eq!(system.nonce(&alice), u8::MAX);
+
+        assert_eq!(system.inc_nonce(&alice), Err(DispatchError::NonceOverflow));
+        assert_eq!(system.nonce(&alice), u8::MAX);
+    }
+
+    #[test]
+    fn kill_account() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(system.nonce(&alice), 2);
+
+        system.kill_account(&alice);
+        assert_eq!(system.nonce(&alice), 0);
+    }
+
+    #[test]
+    fn reference_counting() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        assert!(!system.account_exists(&alice));
+
+        // An account which does not exist cannot hold any state.
+        assert_eq!(
+            system.inc_consumers(&alice),
+            Err(DispatchError::NoProviders)
+        );
+        assert_eq!(system.consumers(&alice), 0);
+
+        system.inc_providers(&alice);
+        system.inc_providers(&alice);
+        assert!(system.account_exists(&alice));
+        assert_eq!(system.providers(&alice), 2);
+        assert_eq!(system.inc_consumers(&alice), Ok(()));
+        assert_eq!(system.consumers(&alice), 1);
+
+        system.dec_consumers(&alice);
+        system.dec_providers(&alice);
+        assert!(system.account_exists(&alice));
+        system.dec_providers(&alice);
+        assert!(!system.account_exists(&alice));
+        assert_eq!(system.providers(&alice), 0);
+        assert_eq!(system.consumers(&alice), 0);
+    }
+
+    #[test]
+    fn account_events() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+
+        system.inc_providers(&alice);
+        system.inc_providers(&alice);
+        assert_eq!(
+            system.take_events(),
+            vec![Event::NewAccount { who: alice.clone() }]
+        );
+
+        system.dec_providers(&alice);
+        assert!(system.take_events().is_empty());
+        system.dec_providers(&alice);
+        assert_eq!(
+            system.take_events(),
+            vec![Event::KilledAccount { who: alice.clone() }]
+        );
+
+        // Accounts which do not exist cannot be killed.
+        system.dec_providers(&alice);
+        system.kill_account(&alice);
+        assert!(system.take_events().is_empty());
+    }
+
+    #[test]
+    fn count_accounts() {
+        let mut system = Pallet::<TestConfig>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let charlie = "charlie".to_string();
+        assert_eq!(system.account_count(), 0);
+
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(system.inc_nonce(&alice), Ok(()));
+        assert_eq!(system.inc_nonce(&bob), Ok(()));
+        assert_eq!(system.account_count(), 2);
+
+        // Funded accounts are counted, even before they make a transaction.
+        system.inc_providers(&charlie);
+        system.inc_providers(&alice);
+        assert_eq!(system.account_count(), 3);
+
+        // An account with a nonce stays known after losing its provider.
+        system.dec_providers(&alice);
+        system.dec_providers(&charlie);
+        assert_eq!(system.account_count(), 2);
+
+        system.kill_account(&alice);
+        system.kill_account(&charlie);
+        assert_eq!(system.account_count(), 1);
+    }
+
+    #[test]
+    fn deposit_events() {
+        let mut system = Pallet::<TestConfig>::new();
+        assert!(system.events().is_empty());
+
+        system.deposit_event("first");
+        system.deposit_event("second");
+        assert_eq!(system.events(), &["first", "second"]);
+
+        system.reset_events();
+        assert!(system.events().is_empty());
+    }
+
+    #[test]
+    fn count_extrinsics() {
+        let mut system = Pallet::<TestConfig>::new();
+        assert_eq!(system.extrinsic_count(), 0);
+
+        system.inc_extrinsic_count();
+        system.inc_extrinsic_count();
+        assert_eq!(system.extrinsic_count(), 2);
+
+        system.reset_extrinsic_count();
+        assert_eq!(system.extrinsic_count(), 0);
+    }
+
+    #[test]
+    fn on_initialize() {
+        let mut system = Pallet::<TestConfig>::new();
+        system.deposit_event("previous block");
+        system.inc_extrinsic_count();
+        system.register_weight(Weight(10));
+        system.register_weight(Weight(5));
+        assert_eq!(system.block_weight(), Weight(15));
+
+        system.on_initialize(1);
+        assert_eq!(system.block_number(), 1);
+        assert!(system.events().is_empty());
+        assert_eq!(system.extrinsic_count(), 0);
+        assert_eq!(system.block_weight(), Weight(0));
+
+        system.on_initialize(2);
+        assert_eq!(system.block_number(), 2);
+        assert_eq!(system.next_block_number(), Some(3));
+
+        system.on_initialize(u32::MAX);
+        assert_eq!(system.next_block_number(), None);
+    }
+
+    #[test]
+    fn store_block_hashes() {
+        let mut system = Pallet::<TestConfig>::new();
+        assert_eq!(system.block_hash(1), None);
+
+        system.set_block_hash(1, 0xaaaa);
+        system.set_block_hash(2, 0xbbbb);
+        assert_eq!(system.block_hash(1), Some(0xaaaa));
+        assert_eq!(system.block_hash(2), Some(0xbbbb));
+        assert_eq!(system.block_hash(3), None);
+    }
+
+    /// Run the same sequence of operations against a system pallet, returning what was observed.
+    fn exercise<T>() -> Vec<String>
+    where
+        T: Config<AccountId = String, BlockNumber = u32, Nonce = u32, Hash = u64>
+            + core::fmt::Debug,
+    {
+        let mut system = Pallet::<T>::new();
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut observed = Vec::new();
+        system.on_initialize(1);
+        observed.push(format!("{:?}", system.inc_nonce(&bob)));
+        observed.push(format!("{:?}", system.inc_nonce(&alice)));
+        observed.push(format!("{:?}", system.inc_nonce(&bob)));
+        system.inc_providers(&alice);
+        observed.push(format!("{:?}", system.inc_consumers(&alice)));
+        observed.push(format!("{:?}", system.inc_consumers(&bob)));
+        system.set_block_hash(1, 0xaaaa);
+        observed.push(format!("{:?}", system.nonces().collect::<Vec<_>>()));
+        observed.push(format!("{:?}", system.encode_storage()));
+        system.dec_consumers(&alice);
+        system.dec_providers(&alice);
+        system.kill_account(&bob);
+        observed.push(format!("{:?}", system.take_events()));
+        observed.push(format!("{:?}", system.account_count()));
+        observed.push(format!("{:?}", system.encode_storage()));
+        observed
+    }
+
+    #[test]
+    fn storage_backends_behave_identically() {
+        let expected = exercise::<TestConfig>();
+        assert_eq!(expected[5], r#"[("alice", 1), ("bob", 2)]"#);
+        assert_eq!(exercise::<VecMapConfig>(), expected);
     }
 }
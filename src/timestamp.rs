@@ -0,0 +1,180 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Debug;
+
+use num::Zero;
+
+use crate::support::{DispatchError, DispatchResult, EncodeStorage, Hooks};
+
+pub trait Config: crate::system::Config {
+    /// The type used to represent a point in time, for example milliseconds since the Unix epoch.
+    type Moment: Zero + Copy + Debug + PartialOrd;
+}
+
+/// The events which can be emitted by the timestamp module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<T: Config> {
+    /// The current time was set to `now`.
+    Set { now: T::Moment },
+}
+
+/// The initial state of the timestamp module, which is applied before block 1 is executed.
+#[derive(Debug, Clone)]
+pub struct GenesisConfig<T: Config> {
+    /// The time the chain starts at.
+    pub now: T::Moment,
+}
+
+impl<T: Config> Default for GenesisConfig<T> {
+    fn default() -> Self {
+        Self {
+            now: T::Moment::zero(),
+        }
+    }
+}
+
+/// This is the Timestamp Module.
+/// It is a simple module which keeps track of the current time of this state machine.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T::Moment: serde::Serialize",
+        deserialize = "T::Moment: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Pallet<T: Config> {
+    /// The current time, which starts at zero.
+    now: T::Moment,
+    /// The events emitted by this module which have not been taken yet.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<Event<T>>,
+}
+
+impl<T: Config> Default for Pallet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Create a new instance of the timestamp module.
+    pub fn new() -> Self {
+        Self {
+            now: T::Moment::zero(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Apply the genesis `config` to this module, which sets the starting time.
+    pub fn build_genesis(&mut self, config: GenesisConfig<T>) -> DispatchResult {
+        self.now = config.now;
+        Ok(())
+    }
+
+    /// Get the current time.
+    pub fn now(&self) -> T::Moment {
+        self.now
+    }
+
+    /// Get the events emitted by this module which have not been taken yet.
+    pub fn events(&self) -> &[Event<T>] {
+        &self.events
+    }
+
+    /// Take all the events emitted by this module, leaving no events behind.
+    pub fn take_events(&mut self) -> Vec<Event<T>> {
+        core::mem::take(&mut self.events)
+    }
+}
+
+impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+impl<T: Config> EncodeStorage for Pallet<T> {
+    /// Everything but the events which have not been taken yet.
+    fn encode_storage(&self) -> String {
+        format!("{:?}", self.now)
+    }
+}
+
+#[macros::call]
+impl<T: Config> Pallet<T> {
+    /// Set the current time to `now`.
+    /// Time can only move forward, so this function returns an error unless `now` is greater than
+    /// the current time.
+    /// NOTE: Any account can set the time. A real blockchain would let the block author provide it
+    /// instead.
+    #[weight(5)]
+    pub fn set(&mut self, _caller: T::AccountId, now: T::Moment) -> DispatchResult {
+        if now <= self.now {
+            return Err(DispatchError::TimestampNotIncreasing);
+        }
+        self.now = now;
+        self.events.push(Event::Set { now });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, Pallet};
+    use crate::support::DispatchError;
+
+    #[derive(Debug, PartialEq)]
+    struct TestConfig;
+
+    impl super::Config for TestConfig {
+        type Moment = u64;
+    }
+
+    impl crate::system::Config for TestConfig {
+        type AccountId = &'static str;
+        type BlockNumber = u32;
+        type Nonce = u32;
+        type RuntimeEvent = ();
+        type Hash = u64;
+        type Hashing = crate::support::DefaultHashing;
+        type Storage<K: Ord, V> = std::collections::BTreeMap<K, V>;
+        const MAX_BLOCK_WEIGHT: crate::support::Weight = crate::support::Weight(100);
+        fn root() -> &'static str {
+            "root"
+        }
+    }
+
+    #[test]
+    fn default_timestamp() {
+        let timestamp = Pallet::<TestConfig>::default();
+        assert_eq!(timestamp.now(), 0);
+    }
+
+    #[test]
+    fn init_timestamp() {
+        let timestamp = Pallet::<TestConfig>::new();
+        assert_eq!(timestamp.now(), 0);
+        assert!(timestamp.events().is_empty());
+    }
+
+    #[test]
+    fn time_only_moves_forward() {
+        let mut timestamp = Pallet::<TestConfig>::new();
+        assert_eq!(timestamp.set("alice", 1000), Ok(()));
+        assert_eq!(timestamp.now(), 1000);
+
+        assert_eq!(
+            timestamp.set("alice", 1000),
+            Err(DispatchError::TimestampNotIncreasing)
+        );
+        assert_eq!(
+            timestamp.set("alice", 999),
+            Err(DispatchError::TimestampNotIncreasing)
+        );
+        assert_eq!(timestamp.now(), 1000);
+
+        assert_eq!(timestamp.set("bob", 2000), Ok(()));
+        assert_eq!(timestamp.now(), 2000);
+        assert_eq!(
+            timestamp.take_events(),
+            vec![Event::Set { now: 1000 }, Event::Set { now: 2000 }]
+        );
+    }
+}